@@ -0,0 +1,131 @@
+use ark_ff::PrimeField;
+use univariate::univariate::UnivariateDense;
+
+// Berlekamp-Welch decoding: recovers the polynomial P of degree < k from n points of which at most
+// (n - k) / 2 are wrong, and returns it with the positions of the wrong points.
+//
+// With e = (n - k) / 2 it looks for a monic error locator E of degree e and Q of degree < e + k with
+//     Q(x_i) = y_i * E(x_i)
+// for every point, which is linear in the coefficients of Q and E. E vanishes at the wrong points,
+// so Q = P * E whenever there are at most e errors, and P = Q / E.
+pub fn decode<F: PrimeField>(points: &[(F, F)], k: usize) -> Option<(UnivariateDense<F>, Vec<usize>)> {
+    if k == 0 || points.len() < k {
+        return None;
+    }
+
+    let errors = (points.len() - k) / 2;
+    let q_len = errors + k;
+
+    // unknowns are q_0..q_{e+k-1} then e_0..e_{e-1}, the leading coefficient of E is 1
+    let mut matrix = Vec::with_capacity(points.len());
+    let mut rhs = Vec::with_capacity(points.len());
+
+    for (x, y) in points {
+        let powers: Vec<F> = std::iter::successors(Some(F::one()), |power| Some(*power * x)).take(q_len.max(errors + 1)).collect();
+
+        let mut row = powers[..q_len].to_vec();
+        row.extend(powers[..errors].iter().map(|power| -*power * y));
+
+        matrix.push(row);
+        rhs.push(*y * powers[errors]);
+    }
+
+    let solution = solve(matrix, rhs)?;
+
+    let q = UnivariateDense::new(solution[..q_len].to_vec());
+    let mut e_coefficients = solution[q_len..].to_vec();
+    e_coefficients.push(F::one());
+    let e = UnivariateDense::new(e_coefficients);
+
+    let (p, remainder) = q.div_rem(&e);
+    if !remainder.is_zero() || p.coefficients.len() > k {
+        return None;
+    }
+
+    let error_positions: Vec<usize> = points.iter().enumerate().filter(|(_, (x, y))| p.evaluate(*x) != *y).map(|(i, _)| i).collect();
+    if error_positions.len() > errors {
+        return None;
+    }
+
+    Some((p, error_positions))
+}
+
+// Gaussian elimination, returns one solution of a possibly underdetermined system or None if it has none.
+fn solve<F: PrimeField>(mut matrix: Vec<Vec<F>>, mut rhs: Vec<F>) -> Option<Vec<F>> {
+    let rows = matrix.len();
+    let cols = matrix.first().map_or(0, |row| row.len());
+    let mut pivot_columns = Vec::new();
+    let mut row = 0;
+
+    for col in 0..cols {
+        let Some(pivot) = (row..rows).find(|r| !matrix[*r][col].is_zero()) else {
+            continue;
+        };
+        matrix.swap(row, pivot);
+        rhs.swap(row, pivot);
+
+        let inverse = matrix[row][col].inverse().unwrap();
+        for value in matrix[row].iter_mut() {
+            *value *= inverse;
+        }
+        rhs[row] *= inverse;
+
+        let pivot_row = matrix[row].clone();
+        let pivot_rhs = rhs[row];
+        for r in 0..rows {
+            if r != row && !matrix[r][col].is_zero() {
+                let factor = matrix[r][col];
+                for (value, pivot_value) in matrix[r].iter_mut().zip(pivot_row.iter()) {
+                    *value -= factor * pivot_value;
+                }
+                rhs[r] -= factor * pivot_rhs;
+            }
+        }
+
+        pivot_columns.push(col);
+        row += 1;
+        if row == rows {
+            break;
+        }
+    }
+
+    // rows without a pivot must read 0 = 0
+    if rhs[row..].iter().any(|value| !value.is_zero()) {
+        return None;
+    }
+
+    // free variables are set to zero
+    let mut solution = vec![F::zero(); cols];
+    for (r, col) in pivot_columns.iter().enumerate() {
+        solution[*col] = rhs[r];
+    }
+
+    Some(solution)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_bn254::Fr;
+
+    #[test]
+    fn test_decode_with_errors() {
+        // P(x) = 3 + 2x + x^2, 7 points so up to 2 errors
+        let p = UnivariateDense::new(vec![Fr::from(3), Fr::from(2), Fr::from(1)]);
+        let mut points: Vec<(Fr, Fr)> = (1..=7).map(|x| (Fr::from(x), p.evaluate(Fr::from(x)))).collect();
+
+        assert_eq!(decode(&points, 3), Some((p.clone(), vec![])));
+
+        points[1].1 += Fr::from(5);
+        points[5].1 = Fr::from(0);
+        assert_eq!(decode(&points, 3), Some((p.clone(), vec![1, 5])));
+
+        // a third error is beyond the decoding radius: either nothing or another polynomial comes back
+        points[3].1 += Fr::from(1);
+        match decode(&points, 3) {
+            None => {},
+            Some((decoded, _)) => assert_ne!(decoded, p)
+        }
+    }
+}
@@ -0,0 +1,192 @@
+use std::sync::mpsc::{channel, Receiver, Sender};
+use std::thread;
+
+use ark_ff::PrimeField;
+
+use crate::interpolation;
+use crate::shamirr_secret_sharing::{ShamirError, ShamirSecretSharing, Share};
+use crate::zk_project::circuit::{Circuit, Layer, Operator};
+
+// What one party sends another in a round: its sub-shares for the round's multiplications,
+// or its output shares when the outputs are opened.
+struct Message<F: PrimeField> {
+    from: usize,
+    round: usize,
+    values: Vec<F>
+}
+
+// Local BGW simulation: every party is a thread holding degree t Shamir shares of the circuit's wires.
+// Addition gates are computed locally. For multiplication gates the local product is a share of a
+// degree 2t polynomial, so every party reshares its product with degree t and combines the
+// sub-shares it receives with the recombination vector (Lagrange coefficients at 0 of all n parties).
+// Degree reduction needs n >= 2t + 1 parties.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BgwError {
+    // degree reduction needs n >= 2t + 1
+    NotEnoughPartiesForMultiplication { parties: usize, threshold: usize },
+    Sharing(ShamirError)
+}
+
+pub struct BgwSimulation<F: PrimeField> {
+    sss: ShamirSecretSharing<F>,
+    num_parties: usize,
+    threshold: usize
+}
+
+impl <F: PrimeField> BgwSimulation<F> {
+    // secure against t colluding parties
+    pub fn new(num_parties: usize, threshold: usize) -> Result<Self, BgwError> {
+        if 2 * threshold + 1 > num_parties {
+            return Err(BgwError::NotEnoughPartiesForMultiplication { parties: num_parties, threshold });
+        }
+
+        let sss = ShamirSecretSharing::new(threshold + 1, num_parties).map_err(BgwError::Sharing)?;
+        Ok(Self { sss, num_parties, threshold })
+    }
+
+    // Shares the inputs, runs the parties and returns the outputs every party opened.
+    pub fn evaluate(&self, circuit: &Circuit<F>, inputs: &[F]) -> Vec<Vec<F>> {
+        let mut rng = rand::thread_rng();
+
+        // party i receives the i-th share of every input
+        let mut input_shares = vec![Vec::with_capacity(inputs.len()); self.num_parties];
+        for input in inputs {
+            for (party, share) in self.sss.evaluate_shares(&self.sss.random_coefficients(*input, &mut rng)).iter().enumerate() {
+                input_shares[party].push(share.value);
+            }
+        }
+
+        let (senders, receivers): (Vec<Sender<Message<F>>>, Vec<_>) = (0..self.num_parties).map(|_| channel()).unzip();
+
+        thread::scope(|scope| {
+            let parties: Vec<_> = receivers.into_iter().zip(input_shares).enumerate().map(|(party, (receiver, shares))| {
+                let senders = senders.clone();
+                scope.spawn(move || self.run_party(party, &circuit.layers, shares, senders, receiver))
+            }).collect();
+
+            parties.into_iter().map(|party| party.join().expect("party panicked")).collect()
+        })
+    }
+
+    fn run_party(&self, party: usize, layers: &[Layer], input_shares: Vec<F>, senders: Vec<Sender<Message<F>>>, receiver: Receiver<Message<F>>) -> Vec<F> {
+        let mut rng = rand::thread_rng();
+        let mut mailbox = Mailbox { receiver, pending: Vec::new(), num_parties: self.num_parties };
+        let recombination = interpolation::lagrange_coefficients(self.sss.indices(), F::zero());
+
+        let mut wires = input_shares;
+
+        // same order as Circuit::evaluate, from the input layer up
+        for (round, layer) in layers.iter().rev().enumerate() {
+            // gates are evaluated on the shares as on plain values: sums are already shares of degree t,
+            // products are shares of degree 2t and have their degree reduced below
+            let mut outputs = layer.evaluate(&wires);
+            let products: Vec<(usize, F)> = layer.gates.iter()
+                .filter(|gate| gate.operator == Operator::Mul)
+                .map(|gate| (gate.output_index, outputs[gate.output_index]))
+                .collect();
+
+            // every party runs the same circuit, so they all agree on whether this round has messages
+            if !products.is_empty() {
+                let sub_sharings: Vec<Vec<Share<F>>> = products.iter()
+                    .map(|(_, product)| self.sss.evaluate_shares(&self.sss.random_coefficients(*product, &mut rng)))
+                    .collect();
+
+                for (to, sender) in senders.iter().enumerate() {
+                    let values = sub_sharings.iter().map(|sub_shares| sub_shares[to].value).collect();
+                    sender.send(Message { from: party, round, values }).expect("party hung up");
+                }
+
+                let received = mailbox.receive_round(round);
+                for (k, (output_index, _)) in products.iter().enumerate() {
+                    outputs[*output_index] = received.iter().zip(recombination.iter()).map(|(values, lambda)| values[k] * lambda).sum();
+                }
+            }
+
+            wires = outputs;
+        }
+
+        // open the outputs to everyone
+        let round = layers.len();
+        for sender in senders.iter() {
+            sender.send(Message { from: party, round, values: wires.clone() }).expect("party hung up");
+        }
+
+        let received = mailbox.receive_round(round);
+        (0..wires.len()).map(|k| {
+            let shares: Vec<Share<F>> = received.iter().zip(self.sss.indices()).take(self.threshold + 1)
+                .map(|(values, index)| Share { index: *index, value: values[k] })
+                .collect();
+
+            ShamirSecretSharing::reconstruct_secret(&shares)
+        }).collect()
+    }
+}
+
+// Messages of a later round can arrive while a party still waits for the current one, they are kept until then.
+struct Mailbox<F: PrimeField> {
+    receiver: Receiver<Message<F>>,
+    pending: Vec<Message<F>>,
+    num_parties: usize
+}
+
+impl <F: PrimeField> Mailbox<F> {
+    // the values every party sent in this round, ordered by sender
+    fn receive_round(&mut self, round: usize) -> Vec<Vec<F>> {
+        let mut received: Vec<Option<Vec<F>>> = vec![None; self.num_parties];
+
+        let (current, later): (Vec<_>, Vec<_>) = self.pending.drain(..).partition(|message| message.round == round);
+        self.pending = later;
+        let mut count = 0;
+        for message in current {
+            received[message.from] = Some(message.values);
+            count += 1;
+        }
+
+        while count < self.num_parties {
+            let message = self.receiver.recv().expect("party hung up");
+            if message.round == round {
+                received[message.from] = Some(message.values);
+                count += 1;
+            } else {
+                self.pending.push(message);
+            }
+        }
+
+        received.into_iter().map(|values| values.unwrap()).collect()
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::zk_project::circuit::Gate;
+    use ark_bn254::Fr;
+
+    fn create_circuit() -> Circuit<Fr> {
+        let layer0 = Layer::new(vec![Gate::new(0, 1, 0, Operator::Add), Gate::new(2, 3, 1, Operator::Mul)]);
+        let layer1 = Layer::new(vec![Gate::new(0, 1, 0, Operator::Add), Gate::new(2, 3, 1, Operator::Mul), Gate::new(4, 5, 2, Operator::Mul), Gate::new(6, 7, 3, Operator::Mul)]);
+
+        Circuit::new(vec![layer0, layer1])
+    }
+
+    #[test]
+    fn test_bgw_matches_plaintext_evaluation() {
+        let mut circuit = create_circuit();
+        let inputs: Vec<Fr> = (1..=8).map(Fr::from).collect();
+
+        let expected = circuit.evaluate(inputs.clone());
+
+        for (num_parties, threshold) in [(3, 1), (5, 2), (7, 2)] {
+            let outputs = BgwSimulation::new(num_parties, threshold).unwrap().evaluate(&circuit, &inputs);
+
+            assert_eq!(outputs.len(), num_parties);
+            assert!(outputs.iter().all(|party_outputs| *party_outputs == expected));
+        }
+    }
+
+    #[test]
+    fn test_bgw_needs_honest_majority() {
+        assert!(matches!(BgwSimulation::<Fr>::new(4, 2), Err(BgwError::NotEnoughPartiesForMultiplication { parties: 4, threshold: 2 })));
+    }
+}
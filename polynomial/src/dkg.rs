@@ -0,0 +1,325 @@
+use ark_ec::CurveGroup;
+use ark_ff::UniformRand;
+use rand::Rng;
+
+use crate::shamirr_secret_sharing::{ShamirError, ShamirSecretSharing, Share};
+use crate::verifiable_secret_sharing::{evaluate_commitments, FeldmanDealing, FeldmanVSS};
+
+// What a party publishes to everyone.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Broadcast<G: CurveGroup> {
+    // Feldman commitments to the dealer's polynomial
+    Commitments(Vec<G::Affine>),
+    // the share from this dealer was missing or did not verify
+    Complaint { against: usize },
+    // a dealer's answer to a complaint: the complainer's share, made public
+    Reveal { to: usize, share: Share<G::ScalarField> }
+}
+
+// In-process stand-in for the network: an authenticated broadcast channel and private channels
+// between every pair of parties. Messages stay on the bus, every party reads what it needs.
+pub struct MessageBus<G: CurveGroup> {
+    broadcasts: Vec<(usize, Broadcast<G>)>,
+    // (from, to, share)
+    private: Vec<(usize, usize, Share<G::ScalarField>)>
+}
+
+impl <G: CurveGroup> MessageBus<G> {
+    pub fn new() -> Self {
+        Self { broadcasts: Vec::new(), private: Vec::new() }
+    }
+
+    pub fn broadcast(&mut self, from: usize, message: Broadcast<G>) {
+        self.broadcasts.push((from, message));
+    }
+
+    pub fn send(&mut self, from: usize, to: usize, share: Share<G::ScalarField>) {
+        self.private.push((from, to, share));
+    }
+
+    pub fn broadcasts(&self) -> &[(usize, Broadcast<G>)] {
+        &self.broadcasts
+    }
+
+    // the shares sent privately to a party, with their senders
+    pub fn inbox(&self, to: usize) -> impl Iterator<Item = (usize, &Share<G::ScalarField>)> {
+        self.private.iter().filter(move |(_, recipient, _)| *recipient == to).map(|(from, _, share)| (*from, share))
+    }
+
+    fn commitments(&self, dealer: usize) -> Option<&[G::Affine]> {
+        self.broadcasts.iter().find_map(|(from, message)| match message {
+            Broadcast::Commitments(commitments) if *from == dealer => Some(commitments.as_slice()),
+            _ => None
+        })
+    }
+
+    // each complainer counts once
+    fn complainers(&self, dealer: usize) -> Vec<usize> {
+        let mut complainers: Vec<usize> = self.broadcasts.iter().filter_map(|(from, message)| match message {
+            Broadcast::Complaint { against } if *against == dealer => Some(*from),
+            _ => None
+        }).collect();
+        complainers.sort();
+        complainers.dedup();
+
+        complainers
+    }
+
+    fn revealed_share(&self, dealer: usize, complainer: usize) -> Option<&Share<G::ScalarField>> {
+        self.broadcasts.iter().find_map(|(from, message)| match message {
+            Broadcast::Reveal { to, share } if *from == dealer && *to == complainer => Some(share),
+            _ => None
+        })
+    }
+}
+
+impl <G: CurveGroup> Default for MessageBus<G> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DkgError {
+    // too many dealers were disqualified for the qualified ones to hide the key
+    NotEnoughQualified { qualified: usize, required: usize }
+}
+
+// What a party ends up with. The joint secret key is the sum of the qualified dealers' secrets and
+// is never computed by anyone, every party only holds its share of it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DkgOutput<G: CurveGroup> {
+    // the dealers whose sharings make up the key, the same for every honest party
+    pub qualified: Vec<usize>,
+    pub share: Share<G::ScalarField>,
+    // sum of the qualified dealers' commitments, commitments[0] is the public key
+    pub commitments: Vec<G::Affine>
+}
+
+impl <G: CurveGroup> DkgOutput<G> {
+    pub fn public_key(&self) -> G::Affine {
+        self.commitments[0]
+    }
+
+    // g^{share} for the share at this index, what a share's holder can be checked against
+    pub fn verification_key(&self, index: G::ScalarField) -> G::Affine {
+        evaluate_commitments::<G>(&self.commitments, index).into_affine()
+    }
+}
+
+// Joint-Feldman DKG (Pedersen's protocol, with the complaint round of Gennaro-Jarecki-Krawczyk-Rabin):
+//  1. every party deals a random secret with Feldman VSS, broadcasting the commitments and sending
+//     share j privately to party j
+//  2. every party complains about each dealer whose share is missing or fails verification
+//  3. a dealer answers each complaint by broadcasting the complainer's share
+//  4. dealers with threshold or more complaints, or with an answer that does not verify, are
+//     disqualified. Everyone decides on the same broadcasts, so all honest parties agree on the rest.
+// Party j's share of the key is the sum of the shares it holds from the qualified dealers.
+pub struct DkgParty<G: CurveGroup> {
+    pub id: usize,
+    sss: ShamirSecretSharing<G::ScalarField>,
+    dealing: Option<FeldmanDealing<G>>,
+    complaints: Vec<usize>
+}
+
+impl <G: CurveGroup> DkgParty<G> {
+    // party id holds the share at x = id + 1
+    pub fn new(id: usize, threshold: usize, num_parties: usize) -> Result<Self, ShamirError> {
+        Ok(Self { id, sss: ShamirSecretSharing::new(threshold, num_parties)?, dealing: None, complaints: Vec::new() })
+    }
+
+    pub fn deal<R: Rng>(&mut self, bus: &mut MessageBus<G>, rng: &mut R) {
+        let dealing = self.vss().deal(G::ScalarField::rand(rng), rng);
+
+        bus.broadcast(self.id, Broadcast::Commitments(dealing.commitments.clone()));
+        for (to, share) in dealing.shares.iter().enumerate() {
+            bus.send(self.id, to, *share);
+        }
+
+        self.dealing = Some(dealing);
+    }
+
+    pub fn check_shares(&mut self, bus: &mut MessageBus<G>) {
+        for dealer in 0..self.sss.num_shares() {
+            if !self.share_is_valid(bus, dealer, self.received_share(bus, dealer)) {
+                self.complaints.push(dealer);
+                bus.broadcast(self.id, Broadcast::Complaint { against: dealer });
+            }
+        }
+    }
+
+    pub fn answer_complaints(&self, bus: &mut MessageBus<G>) {
+        let Some(dealing) = &self.dealing else {
+            return;
+        };
+
+        for complainer in bus.complainers(self.id) {
+            bus.broadcast(self.id, Broadcast::Reveal { to: complainer, share: dealing.shares[complainer] });
+        }
+    }
+
+    pub fn finish(&self, bus: &MessageBus<G>) -> Result<DkgOutput<G>, DkgError> {
+        let qualified: Vec<usize> = (0..self.sss.num_shares()).filter(|dealer| self.is_qualified(bus, *dealer)).collect();
+
+        // at most threshold - 1 parties are corrupt, so this many qualified dealers include an honest one
+        let required = self.sss.threshold();
+        if qualified.len() < required {
+            return Err(DkgError::NotEnoughQualified { qualified: qualified.len(), required });
+        }
+
+        let index = self.sss.indices()[self.id];
+        let mut value = G::ScalarField::from(0u64);
+        let mut commitments = vec![G::zero(); required];
+
+        for dealer in qualified.iter() {
+            // a dealer we complained about stays qualified only if it revealed a valid share to us
+            let share = if self.complaints.contains(dealer) {
+                bus.revealed_share(*dealer, self.id)
+            } else {
+                self.received_share(bus, *dealer)
+            };
+            value += share.expect("qualified dealers delivered a share").value;
+
+            for (sum, commitment) in commitments.iter_mut().zip(bus.commitments(*dealer).unwrap()) {
+                *sum += commitment;
+            }
+        }
+
+        Ok(DkgOutput { qualified, share: Share { index, value }, commitments: G::normalize_batch(&commitments) })
+    }
+
+    fn vss(&self) -> FeldmanVSS<G> {
+        FeldmanVSS::new(self.sss.clone())
+    }
+
+    fn received_share<'a>(&self, bus: &'a MessageBus<G>, dealer: usize) -> Option<&'a Share<G::ScalarField>> {
+        bus.inbox(self.id).find(|(from, _)| *from == dealer).map(|(_, share)| share)
+    }
+
+    // the share must be at the complainer's index and lie on the dealer's committed polynomial
+    fn share_is_valid(&self, bus: &MessageBus<G>, dealer: usize, share: Option<&Share<G::ScalarField>>) -> bool {
+        match (bus.commitments(dealer), share) {
+            (Some(commitments), Some(share)) => share.index == self.sss.indices()[self.id] && self.vss().verify_share(commitments, share),
+            _ => false
+        }
+    }
+
+    fn is_qualified(&self, bus: &MessageBus<G>, dealer: usize) -> bool {
+        let Some(commitments) = bus.commitments(dealer) else {
+            return false;
+        };
+        if commitments.len() != self.sss.threshold() {
+            return false;
+        }
+
+        let complainers = bus.complainers(dealer);
+        if complainers.len() >= self.sss.threshold() {
+            return false;
+        }
+
+        complainers.iter().all(|complainer| {
+            bus.revealed_share(dealer, *complainer).is_some_and(|share| {
+                share.index == self.sss.indices()[*complainer] && self.vss().verify_share(commitments, share)
+            })
+        })
+    }
+}
+
+// Runs the protocol with every party honest and returns each party's output.
+pub fn run_dkg<G: CurveGroup, R: Rng>(threshold: usize, num_parties: usize, rng: &mut R) -> Result<Vec<DkgOutput<G>>, ShamirError> {
+    let mut parties = (0..num_parties).map(|id| DkgParty::<G>::new(id, threshold, num_parties)).collect::<Result<Vec<_>, _>>()?;
+    let mut bus = MessageBus::new();
+
+    for party in parties.iter_mut() {
+        party.deal(&mut bus, rng);
+    }
+    for party in parties.iter_mut() {
+        party.check_shares(&mut bus);
+    }
+    for party in parties.iter() {
+        party.answer_complaints(&mut bus);
+    }
+
+    Ok(parties.iter().map(|party| party.finish(&bus).expect("honest parties are all qualified")).collect())
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_ec::PrimeGroup;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    // g^{secret key}, which only the tests can compute by gathering the shares
+    fn public_key_of<G: CurveGroup>(shares: &[Share<G::ScalarField>]) -> G::Affine {
+        (G::generator() * ShamirSecretSharing::reconstruct_secret(shares)).into_affine()
+    }
+
+    #[test]
+    fn test_honest_dkg() {
+        type G = ark_bn254::G1Projective;
+        let mut rng = StdRng::seed_from_u64(0);
+
+        let outputs = run_dkg::<G, _>(3, 5, &mut rng).unwrap();
+        let shares: Vec<_> = outputs.iter().map(|output| output.share).collect();
+
+        assert!(outputs.iter().all(|output| output.qualified == vec![0, 1, 2, 3, 4] && output.commitments == outputs[0].commitments));
+        assert_eq!(public_key_of::<G>(&shares[..3]), outputs[0].public_key());
+        assert_eq!(public_key_of::<G>(&shares[2..]), outputs[0].public_key());
+        assert!(shares.iter().all(|share| outputs[0].verification_key(share.index) == (G::generator() * share.value).into_affine()));
+    }
+
+    #[test]
+    fn test_complaints() {
+        type G = ark_bls12_381::G1Projective;
+        let mut rng = StdRng::seed_from_u64(1);
+
+        let mut parties: Vec<DkgParty<G>> = (0..5).map(|id| DkgParty::new(id, 3, 5).unwrap()).collect();
+        let mut bus = MessageBus::new();
+        for party in parties.iter_mut() {
+            party.deal(&mut bus, &mut rng);
+        }
+
+        // dealer 1 sends a bad share to party 3 and dealer 4 to parties 0 and 2
+        for (from, to, share) in bus.private.iter_mut() {
+            if (*from, *to) == (1, 3) || (*from == 4 && (*to == 0 || *to == 2)) {
+                share.value += ark_bls12_381::Fr::from(1);
+            }
+        }
+
+        for party in parties.iter_mut() {
+            party.check_shares(&mut bus);
+        }
+        assert_eq!(bus.complainers(1), vec![3]);
+        assert_eq!(bus.complainers(4), vec![0, 2]);
+
+        // dealer 1 answers with the right share, dealer 4 stays silent
+        for party in parties[..4].iter() {
+            party.answer_complaints(&mut bus);
+        }
+
+        let outputs: Vec<DkgOutput<G>> = parties.iter().map(|party| party.finish(&bus).unwrap()).collect();
+        let shares: Vec<_> = outputs.iter().map(|output| output.share).collect();
+
+        assert!(outputs.iter().all(|output| output.qualified == vec![0, 1, 2, 3] && output.public_key() == outputs[0].public_key()));
+        assert_eq!(public_key_of::<G>(&shares[..3]), outputs[0].public_key());
+        assert_eq!(public_key_of::<G>(&shares[1..4]), outputs[0].public_key());
+    }
+
+    #[test]
+    fn test_too_many_disqualified() {
+        type G = ark_bn254::G1Projective;
+        let mut rng = StdRng::seed_from_u64(2);
+
+        let mut parties: Vec<DkgParty<G>> = (0..3).map(|id| DkgParty::new(id, 2, 3).unwrap()).collect();
+        let mut bus = MessageBus::new();
+
+        // only party 0 deals
+        parties[0].deal(&mut bus, &mut rng);
+        for party in parties.iter_mut() {
+            party.check_shares(&mut bus);
+        }
+
+        assert_eq!(parties[0].finish(&bus), Err(DkgError::NotEnoughQualified { qualified: 1, required: 2 }));
+    }
+}
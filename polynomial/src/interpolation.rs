@@ -19,6 +19,16 @@ pub(crate) fn lagrange_interpolation<F: PrimeField>(points: &[(F, F)], x: F) ->
     result
 }
 
+// Lagrange basis polynomials of the given x-coordinates evaluated at x, so that
+// f(x) = sum_i coefficients[i] * f(xs[i]) for every f of degree < xs.len()
+pub(crate) fn lagrange_coefficients<F: PrimeField>(xs: &[F], x: F) -> Vec<F> {
+    xs.iter().enumerate().map(|(i, x_i)| {
+        xs.iter().enumerate()
+            .filter(|(j, _)| *j != i)
+            .fold(F::one(), |li, (_, x_j)| li * (x - x_j) * (*x_i - x_j).inverse().unwrap())
+    }).collect()
+}
+
 fn main() { 
 
     // use ark_bls12_381::Fr;
@@ -1,5 +1,13 @@
 pub mod sum_of_an_nth_term;
 pub mod shamirr_secret_sharing;
+pub mod verifiable_secret_sharing;
+pub mod berlekamp_welch;
+pub mod reed_solomon;
+pub mod share_encoding;
+pub mod packed_secret_sharing;
+pub mod bgw_mpc;
+pub mod dkg;
+pub mod threshold_bls;
 pub mod Addition_of_two_array;
 pub mod interpolation;
 pub mod zk_project;
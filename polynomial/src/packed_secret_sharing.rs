@@ -0,0 +1,155 @@
+use ark_ff::PrimeField;
+use rand::Rng;
+
+use crate::interpolation;
+use crate::shamirr_secret_sharing::Share;
+
+// Packed (Franklin-Yung) secret sharing: k secrets are the values of one polynomial of degree t + k - 1
+// at k designated points, and the shares are its values at the holders' indices.
+// Any t shares reveal nothing about the secrets, t + k shares reconstruct all of them, and in between
+// the secrets are only partially hidden. Every holder stores one field element for all k secrets.
+pub struct PackedSecretSharing<F: PrimeField> {
+    threshold: usize,
+    secret_points: Vec<F>,
+    indices: Vec<F>
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PackedSharingError {
+    ZeroThreshold,
+    NoSecrets,
+    // t + k shares are needed to reconstruct
+    ThresholdExceedsShares { threshold: usize, shares: usize },
+    // a point is used twice among the secret points and the share indices
+    DuplicatePoint
+}
+
+impl<F: PrimeField> PackedSecretSharing<F> {
+    // secrets at x = 0, -1, ..., -(k - 1) and shares at x = 1, ..., shares
+    pub fn new(threshold: usize, num_secrets: usize, shares: usize) -> Result<Self, PackedSharingError> {
+        let secret_points = (0..num_secrets).map(|i| -F::from(i as u64)).collect();
+        let indices = (1..=shares).map(|i| F::from(i as u64)).collect();
+
+        Self::with_points(threshold, secret_points, indices)
+    }
+
+    // caller chosen points, the secret points and the share indices must all be distinct
+    pub fn with_points(threshold: usize, secret_points: Vec<F>, indices: Vec<F>) -> Result<Self, PackedSharingError> {
+        if threshold == 0 {
+            return Err(PackedSharingError::ZeroThreshold);
+        }
+        if secret_points.is_empty() {
+            return Err(PackedSharingError::NoSecrets);
+        }
+        if threshold + secret_points.len() > indices.len() {
+            return Err(PackedSharingError::ThresholdExceedsShares { threshold: threshold + secret_points.len(), shares: indices.len() });
+        }
+
+        let all_points: Vec<&F> = secret_points.iter().chain(indices.iter()).collect();
+        if all_points.iter().enumerate().any(|(i, point)| all_points[i + 1..].contains(point)) {
+            return Err(PackedSharingError::DuplicatePoint);
+        }
+
+        Ok(Self { threshold, secret_points, indices })
+    }
+
+    pub fn threshold(&self) -> usize {
+        self.threshold
+    }
+
+    pub fn num_secrets(&self) -> usize {
+        self.secret_points.len()
+    }
+
+    // number of shares needed to reconstruct
+    pub fn reconstruction_threshold(&self) -> usize {
+        self.threshold + self.secret_points.len()
+    }
+
+    pub fn split_secrets(&self, secrets: &[F]) -> Vec<Share<F>> {
+        self.split_secrets_with_rng(secrets, &mut rand::thread_rng())
+    }
+
+    // The polynomial is fixed by the secrets and by random values at the first t share indices.
+    pub fn split_secrets_with_rng<R: Rng>(&self, secrets: &[F], rng: &mut R) -> Vec<Share<F>> {
+        assert!(secrets.len() == self.secret_points.len(), "wrong number of secrets");
+
+        let mut points: Vec<(F, F)> = self.secret_points.iter().copied().zip(secrets.iter().copied()).collect();
+        points.extend(self.indices.iter().take(self.threshold).map(|index| (*index, F::rand(rng))));
+
+        let xs: Vec<F> = points.iter().map(|(x, _)| *x).collect();
+
+        self.indices.iter().map(|index| {
+            let value = interpolation::lagrange_coefficients(&xs, *index).iter().zip(points.iter()).map(|(l, (_, y))| *l * y).sum();
+            Share { index: *index, value }
+        }).collect()
+    }
+
+    // None with fewer than t + k shares or when two shares have the same index
+    pub fn reconstruct_secrets(&self, shares: &[Share<F>]) -> Option<Vec<F>> {
+        if shares.len() < self.reconstruction_threshold() {
+            return None;
+        }
+        if shares.iter().enumerate().any(|(i, share)| shares[i + 1..].iter().any(|other| other.index == share.index)) {
+            return None;
+        }
+
+        let points: Vec<(F, F)> = shares.iter().take(self.reconstruction_threshold()).map(|share| (share.index, share.value)).collect();
+
+        Some(self.secret_points.iter().map(|x| interpolation::lagrange_interpolation(&points, *x)).collect())
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_bn254::Fr;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    fn secrets() -> Vec<Fr> {
+        vec![Fr::from(11), Fr::from(22), Fr::from(33)]
+    }
+
+    #[test]
+    fn test_packed_sharing() {
+        let pss = PackedSecretSharing::<Fr>::new(2, 3, 8).unwrap();
+        let shares = pss.split_secrets(&secrets());
+
+        assert_eq!(shares.len(), 8);
+        assert_eq!(pss.reconstruct_secrets(&shares[3..]), Some(secrets()));
+        assert_eq!(pss.reconstruct_secrets(&shares[4..]), None);
+
+        // a repeated share does not count twice
+        let repeated = [&shares[3..7], &shares[3..4]].concat();
+        assert_eq!(pss.reconstruct_secrets(&repeated), None);
+
+        assert!(matches!(PackedSecretSharing::<Fr>::new(2, 3, 4), Err(PackedSharingError::ThresholdExceedsShares { threshold: 5, shares: 4 })));
+        assert!(matches!(PackedSecretSharing::<Fr>::new(2, 0, 4), Err(PackedSharingError::NoSecrets)));
+        assert!(matches!(PackedSecretSharing::<Fr>::with_points(1, vec![Fr::from(1)], vec![Fr::from(1), Fr::from(2)]), Err(PackedSharingError::DuplicatePoint)));
+    }
+
+    #[test]
+    fn test_privacy_threshold_trade_off() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let pss = PackedSecretSharing::<Fr>::new(2, 3, 8).unwrap();
+        let shares = pss.split_secrets_with_rng(&secrets(), &mut rng);
+
+        // t shares are consistent with any secrets: completing the first t shares with the other shares of
+        // the degree t + k - 1 polynomial through them and the fake secrets gives a valid sharing of the fakes
+        let fake_secrets = vec![Fr::from(1), Fr::from(2), Fr::from(3)];
+        let mut points: Vec<(Fr, Fr)> = shares[..2].iter().map(|share| (share.index, share.value)).collect();
+        points.extend(pss.secret_points.iter().copied().zip(fake_secrets.iter().copied()));
+
+        let mut completed = shares[..2].to_vec();
+        completed.extend(pss.indices[2..].iter().map(|index| Share { index: *index, value: interpolation::lagrange_interpolation(&points, *index) }));
+        assert_eq!(pss.reconstruct_secrets(&completed), Some(fake_secrets));
+        assert_eq!(pss.reconstruct_secrets(&shares), Some(secrets()));
+
+        // a single extra share already leaks: with t + 1 shares, knowing k - 1 secrets reveals the last one,
+        // where k independent Shamir sharings with threshold t would still hide it
+        let mut points: Vec<(Fr, Fr)> = shares[..3].iter().map(|share| (share.index, share.value)).collect();
+        points.extend(pss.secret_points[1..].iter().copied().zip(secrets()[1..].iter().copied()));
+        assert_eq!(interpolation::lagrange_interpolation(&points, pss.secret_points[0]), secrets()[0]);
+    }
+}
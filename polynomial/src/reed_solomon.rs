@@ -0,0 +1,188 @@
+use ark_ff::PrimeField;
+use univariate::{ntt::NttDomain, univariate::UnivariateDense};
+
+use crate::berlekamp_welch;
+
+// Where codewords are evaluated: a multiplicative subgroup, encoded with the NTT, or any distinct points.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EvaluationPoints<F: PrimeField> {
+    Domain(NttDomain<F>),
+    Points(Vec<F>)
+}
+
+// Reed-Solomon code of message length k and codeword length n: a message is the coefficients of a
+// polynomial of degree < k and its codeword the evaluations at the n points. Any two codewords differ
+// in at least n - k + 1 positions, so up to (n - k) / 2 errors can be corrected.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReedSolomon<F: PrimeField> {
+    message_length: usize,
+    points: EvaluationPoints<F>
+}
+
+impl <F: PrimeField> ReedSolomon<F> {
+    // rate 1 / blowup over the subgroup of size message length * blowup, both powers of two.
+    // None if either is not a power of two or the field has no such subgroup.
+    pub fn new(message_length: usize, blowup: usize) -> Option<Self> {
+        if !message_length.is_power_of_two() || !blowup.is_power_of_two() {
+            return None;
+        }
+
+        Some(Self { message_length, points: EvaluationPoints::Domain(NttDomain::new(message_length * blowup)?) })
+    }
+
+    // None if there are fewer points than the message length or a point repeats
+    pub fn with_points(message_length: usize, points: Vec<F>) -> Option<Self> {
+        if message_length == 0 || message_length > points.len() {
+            return None;
+        }
+        if points.iter().enumerate().any(|(i, point)| points[i + 1..].contains(point)) {
+            return None;
+        }
+
+        Some(Self { message_length, points: EvaluationPoints::Points(points) })
+    }
+
+    pub fn message_length(&self) -> usize {
+        self.message_length
+    }
+
+    pub fn codeword_length(&self) -> usize {
+        match &self.points {
+            EvaluationPoints::Domain(domain) => domain.size,
+            EvaluationPoints::Points(points) => points.len()
+        }
+    }
+
+    pub fn points(&self) -> Vec<F> {
+        match &self.points {
+            EvaluationPoints::Domain(domain) => domain.elements(),
+            EvaluationPoints::Points(points) => points.clone()
+        }
+    }
+
+    // the number of errors decode corrects
+    pub fn max_errors(&self) -> usize {
+        (self.codeword_length() - self.message_length) / 2
+    }
+
+    pub fn encode(&self, message: &[F]) -> Vec<F> {
+        assert!(message.len() <= self.message_length, "message too long for this code");
+
+        match &self.points {
+            EvaluationPoints::Domain(domain) => domain.fft(message),
+            EvaluationPoints::Points(points) => {
+                let poly = UnivariateDense::new(message.to_vec());
+                points.iter().map(|point| poly.evaluate(*point)).collect()
+            }
+        }
+    }
+
+    // The message of the codeword within max_errors of the received word, padded to the message
+    // length, and the positions where the two differ. None beyond the unique decoding radius.
+    pub fn decode(&self, received: &[F]) -> Option<(Vec<F>, Vec<usize>)> {
+        if received.len() != self.codeword_length() {
+            return None;
+        }
+
+        // a codeword is recognised without solving the Berlekamp-Welch system
+        let message = match self.interpolate(received) {
+            Some(message) => message,
+            None => {
+                let points: Vec<(F, F)> = self.points().into_iter().zip(received.iter().copied()).collect();
+                berlekamp_welch::decode(&points, self.message_length)?.0.coefficients
+            }
+        };
+
+        let codeword = self.encode(&message);
+        let errors: Vec<usize> = (0..received.len()).filter(|i| codeword[*i] != received[*i]).collect();
+
+        let mut message = message;
+        message.resize(self.message_length, F::zero());
+
+        Some((message, errors))
+    }
+
+    // Proximity test: whether the word is within the given number of errors of a codeword.
+    // Only answered up to the unique decoding radius, farther distances are reported as not close.
+    pub fn is_close(&self, word: &[F], max_distance: usize) -> bool {
+        self.decode(word).is_some_and(|(_, errors)| errors.len() <= max_distance)
+    }
+
+    pub fn is_codeword(&self, word: &[F]) -> bool {
+        word.len() == self.codeword_length() && self.interpolate(word).is_some()
+    }
+
+    // the message if the word is a codeword
+    fn interpolate(&self, word: &[F]) -> Option<Vec<F>> {
+        let mut coefficients = match &self.points {
+            EvaluationPoints::Domain(domain) => {
+                let coefficients = domain.ifft(word);
+                if coefficients[self.message_length..].iter().any(|coefficient| !coefficient.is_zero()) {
+                    return None;
+                }
+                coefficients
+            },
+            EvaluationPoints::Points(points) => {
+                let first: Vec<(F, F)> = points.iter().copied().zip(word.iter().copied()).take(self.message_length).collect();
+                let poly = UnivariateDense::interpolate(&first);
+                if points.iter().zip(word.iter()).skip(self.message_length).any(|(point, value)| poly.evaluate(*point) != *value) {
+                    return None;
+                }
+                poly.coefficients
+            }
+        };
+
+        coefficients.resize(self.message_length, F::zero());
+        Some(coefficients)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_bn254::Fr;
+    use ark_ff::UniformRand;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    #[test]
+    fn test_encode_decode_over_domain() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let code = ReedSolomon::<Fr>::new(4, 4).unwrap();
+        let message: Vec<Fr> = (0..4).map(|_| Fr::rand(&mut rng)).collect();
+        let mut word = code.encode(&message);
+
+        assert_eq!(code.codeword_length(), 16);
+        assert_eq!(code.max_errors(), 6);
+        assert!(code.is_codeword(&word));
+        assert_eq!(code.decode(&word), Some((message.clone(), vec![])));
+
+        for position in [0, 3, 5, 8, 13, 15] {
+            word[position] += Fr::from(1);
+        }
+        assert!(!code.is_codeword(&word));
+        assert_eq!(code.decode(&word), Some((message, vec![0, 3, 5, 8, 13, 15])));
+        assert!(code.is_close(&word, 6));
+        assert!(!code.is_close(&word, 5));
+
+        assert_eq!(ReedSolomon::<Fr>::new(3, 4), None);
+        assert_eq!(ReedSolomon::<Fr>::new(4, 3), None);
+    }
+
+    #[test]
+    fn test_arbitrary_points() {
+        let points: Vec<Fr> = [3, 9, 10, 20, 21, 50, 51].into_iter().map(Fr::from).collect();
+        let code = ReedSolomon::with_points(3, points.clone()).unwrap();
+        let message = vec![Fr::from(5), Fr::from(0), Fr::from(2)];
+
+        let mut word = code.encode(&message);
+        assert_eq!(word[0], Fr::from(5 + 2 * 9));
+
+        word[6] = Fr::from(0);
+        word[2] += Fr::from(7);
+        assert_eq!(code.decode(&word), Some((message, vec![2, 6])));
+
+        assert_eq!(ReedSolomon::with_points(8, points), None);
+        assert_eq!(ReedSolomon::with_points(2, vec![Fr::from(1), Fr::from(1), Fr::from(2)]), None);
+    }
+}
@@ -1,48 +1,223 @@
-use ark_ff::{PrimeField, UniformRand};
-use std::collections::HashMap;
-use std::ops::Add;
-use crate::interpolation;
+use ark_ff::{BigInteger, PrimeField};
+use rand::Rng;
+use crate::{interpolation, reed_solomon::ReedSolomon};
 
 extern crate rand;
 
+// A point (index, value) on the sharing polynomial, held by the party at x = index.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Share<F: PrimeField> {
+    pub index: F,
+    pub value: F
+}
+
+// The shares of a byte string: one value per chunk, all at the same index.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ByteShare<F: PrimeField> {
+    pub index: F,
+    pub values: Vec<F>
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ShamirError {
+    ZeroThreshold,
+    ThresholdExceedsShares { threshold: usize, shares: usize },
+    // x = 0 would hand out the secret itself
+    ZeroIndex,
+    DuplicateIndex
+}
+
+#[derive(Clone)]
 pub struct ShamirSecretSharing <F : PrimeField> {
     threshold: usize,
-    shares: usize,
-    _field: std::marker::PhantomData<F>,
+    indices: Vec<F>
 }
 
 impl<F: PrimeField> ShamirSecretSharing<F> {
-    pub fn new(threshold: usize, shares: usize) -> Self {
-        ShamirSecretSharing { 
-            threshold,
-             shares,
-             _field: std::marker::PhantomData,}
+    // shares at x = 1, ..., shares
+    pub fn new(threshold: usize, shares: usize) -> Result<Self, ShamirError> {
+        Self::with_indices(threshold, (1..=shares).map(|i| F::from(i as u64)).collect())
     }
 
-    pub fn split_secret(&self, secret: F) -> HashMap<usize, F> {
-        let mut rng = rand::thread_rng();
+    // shares at caller chosen x-coordinates, which must be non-zero and distinct
+    pub fn with_indices(threshold: usize, indices: Vec<F>) -> Result<Self, ShamirError> {
+        if threshold == 0 {
+            return Err(ShamirError::ZeroThreshold);
+        }
+        if threshold > indices.len() {
+            return Err(ShamirError::ThresholdExceedsShares { threshold, shares: indices.len() });
+        }
+        if indices.iter().any(|index| index.is_zero()) {
+            return Err(ShamirError::ZeroIndex);
+        }
+        if has_duplicates(&indices) {
+            return Err(ShamirError::DuplicateIndex);
+        }
+
+        Ok(ShamirSecretSharing { threshold, indices })
+    }
+
+    pub fn threshold(&self) -> usize {
+        self.threshold
+    }
+
+    pub fn num_shares(&self) -> usize {
+        self.indices.len()
+    }
+
+    pub fn indices(&self) -> &[F] {
+        &self.indices
+    }
+
+    pub fn split_secret(&self, secret: F) -> Vec<Share<F>> {
+        let coefficients = self.random_coefficients(secret, &mut rand::thread_rng());
+
+        self.evaluate_shares(&coefficients)
+    }
+
+    // coefficients of a random polynomial of degree threshold - 1 with the secret as constant term
+    pub fn random_coefficients<R: Rng>(&self, secret: F, rng: &mut R) -> Vec<F> {
         let mut coefficients = vec![secret];
         for _ in 1..self.threshold {
-            coefficients.push(F::rand(&mut rng));
+            coefficients.push(F::rand(rng));
         }
 
-        let mut shares = HashMap::new();
-        for i in 1..=self.shares {
-            let mut share = F::zero();
-            let x = F::from(i as u64);
-            for (j, coeff) in coefficients.iter().enumerate() {
-                share += *coeff * x.pow(&[j as u64 ]);
+        coefficients
+    }
+
+    // the polynomial evaluated at every index
+    pub fn evaluate_shares(&self, coefficients: &[F]) -> Vec<Share<F>> {
+        self.indices.iter().map(|index| Share { index: *index, value: evaluate(coefficients, *index) }).collect()
+    }
+
+    pub fn reconstruct_secret(shares: &[Share<F>]) -> F {
+        let points: Vec<(F, F)> = shares.iter().map(|share| (share.index, share.value)).collect();
+        interpolation::lagrange_interpolation(&points, F::zero())
+    }
+
+    // Byte strings are framed with their length and cut into chunks small enough to be field elements,
+    // every chunk is shared with its own polynomial.
+    pub fn split_bytes(&self, secret: &[u8]) -> Vec<ByteShare<F>> {
+        let mut rng = rand::thread_rng();
+        let mut shares: Vec<ByteShare<F>> = self.indices.iter().map(|index| ByteShare { index: *index, values: vec![] }).collect();
+
+        for chunk in encode_bytes::<F>(secret) {
+            for (share, chunk_share) in shares.iter_mut().zip(self.evaluate_shares(&self.random_coefficients(chunk, &mut rng))) {
+                share.values.push(chunk_share.value);
             }
-            shares.insert(i, share);
         }
 
         shares
     }
 
-    pub fn reconstruct_secret(shares: &HashMap<usize, F>) -> F {
-        let points: Vec<(F, F)> = shares.iter().map(|(&x, &y)| (F::from(x as u64), y)).collect();
-        interpolation::lagrange_interpolation(&points, F::zero())
+    // None when the shares disagree on the number of chunks or the framing is invalid
+    pub fn reconstruct_bytes(shares: &[ByteShare<F>]) -> Option<Vec<u8>> {
+        let num_chunks = shares.first()?.values.len();
+        if shares.iter().any(|share| share.values.len() != num_chunks) {
+            return None;
+        }
+
+        let chunks: Vec<F> = (0..num_chunks)
+            .map(|chunk| {
+                let chunk_shares: Vec<Share<F>> = shares.iter().map(|share| Share { index: share.index, value: share.values[chunk] }).collect();
+                Self::reconstruct_secret(&chunk_shares)
+            })
+            .collect();
+
+        decode_bytes(&chunks)
+    }
+
+    // Proactive refresh: every holder deals a sharing of zero and each share is increased by the
+    // sub-shares it receives. The secret is unchanged, but shares from before and after the refresh
+    // lie on different polynomials and cannot be combined.
+    pub fn refresh_shares<R: Rng>(&self, shares: &[Share<F>], rng: &mut R) -> Vec<Share<F>> {
+        let mut refreshed = shares.to_vec();
+
+        for _ in shares {
+            let zero_sharing = self.random_coefficients(F::zero(), rng);
+            for share in refreshed.iter_mut() {
+                share.value += evaluate(&zero_sharing, share.index);
+            }
+        }
+
+        refreshed
+    }
+
+    // Resharing to a new committee: `threshold` of the current holders each share their share with
+    // `new_sharing`, and new holder i combines the sub-shares it receives with the Lagrange coefficients
+    // at 0 of the dealers. None when there are fewer shares than the current threshold.
+    pub fn reshare<R: Rng>(&self, shares: &[Share<F>], new_sharing: &ShamirSecretSharing<F>, rng: &mut R) -> Option<Vec<Share<F>>> {
+        if shares.len() < self.threshold {
+            return None;
+        }
+
+        let dealers = &shares[..self.threshold];
+        let xs: Vec<F> = dealers.iter().map(|share| share.index).collect();
+        let lambdas = interpolation::lagrange_coefficients(&xs, F::zero());
+
+        let mut new_shares: Vec<Share<F>> = new_sharing.indices.iter().map(|index| Share { index: *index, value: F::zero() }).collect();
+        for (dealer, lambda) in dealers.iter().zip(lambdas.iter()) {
+            let sub_shares = new_sharing.evaluate_shares(&new_sharing.random_coefficients(dealer.value, rng));
+            for (share, sub_share) in new_shares.iter_mut().zip(sub_shares.iter()) {
+                share.value += *lambda * sub_share.value;
+            }
+        }
+
+        Some(new_shares)
+    }
+
+    // Reconstructs from n shares of which up to (n - threshold) / 2 may be corrupted, returning
+    // the secret together with the indices of the faulty shares.
+    // None when there are more errors than that, or fewer shares than the threshold.
+    pub fn reconstruct_secret_robust(&self, shares: &[Share<F>]) -> Option<(F, Vec<F>)> {
+        let code = ReedSolomon::with_points(self.threshold, shares.iter().map(|share| share.index).collect())?;
+        let values: Vec<F> = shares.iter().map(|share| share.value).collect();
+        let (coefficients, error_positions) = code.decode(&values)?;
+
+        Some((coefficients[0], error_positions.iter().map(|position| shares[*position].index).collect()))
+    }
+}
+
+fn evaluate<F: PrimeField>(coefficients: &[F], x: F) -> F {
+    coefficients.iter().rev().fold(F::zero(), |acc, coefficient| acc * x + coefficient)
+}
+
+fn has_duplicates<F: PrimeField>(indices: &[F]) -> bool {
+    indices.iter().enumerate().any(|(i, index)| indices[i + 1..].contains(index))
+}
+
+// bytes per chunk, so that every chunk is below the modulus
+fn chunk_size<F: PrimeField>() -> usize {
+    (F::MODULUS_BIT_SIZE as usize - 1) / 8
+}
+
+// the first element is the length in bytes, followed by big-endian chunks
+fn encode_bytes<F: PrimeField>(bytes: &[u8]) -> Vec<F> {
+    let mut elements = vec![F::from(bytes.len() as u64)];
+    elements.extend(bytes.chunks(chunk_size::<F>()).map(F::from_be_bytes_mod_order));
+
+    elements
+}
+
+fn decode_bytes<F: PrimeField>(elements: &[F]) -> Option<Vec<u8>> {
+    let (length_element, chunks) = elements.split_first()?;
+    let length: usize = length_element.into_bigint().as_ref()[0].try_into().ok()?;
+
+    if *length_element != F::from(length as u64) || chunks.len() != length.div_ceil(chunk_size::<F>()) {
+        return None;
     }
+
+    let mut bytes = Vec::with_capacity(length);
+    for (i, chunk) in chunks.iter().enumerate() {
+        let chunk_length = chunk_size::<F>().min(length - i * chunk_size::<F>());
+        let chunk_bytes = chunk.into_bigint().to_bytes_be();
+        if chunk_bytes[..chunk_bytes.len() - chunk_length].iter().any(|byte| *byte != 0) {
+            return None;
+        }
+        bytes.extend_from_slice(&chunk_bytes[chunk_bytes.len() - chunk_length..]);
+    }
+
+    Some(bytes)
 }
 
 #[cfg(test)]
@@ -50,16 +225,102 @@ mod tests {
     use super::*;
     // use ark_bls12_381::Fr;
     use ark_bn254::Fr;
+    use rand::{rngs::StdRng, SeedableRng};
 
     #[test]
     fn test_shamir_secret_sharing() {
-        let sss = ShamirSecretSharing::<Fr>::new(3, 5);
+        let sss = ShamirSecretSharing::<Fr>::new(3, 5).unwrap();
         let secret =Fr::from(123456u64);
         let shares = sss.split_secret(secret);
 
-        let selected_shares: HashMap<usize, Fr> = shares.iter().take(3).map(|(&k, &v)| (k, v)).collect();
+        let selected_shares: Vec<Share<Fr>> = shares.iter().take(3).copied().collect();
         let reconstructed_secret = ShamirSecretSharing::reconstruct_secret(&selected_shares);
 
         assert_eq!(secret, reconstructed_secret);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_sharing_validation() {
+        assert!(matches!(ShamirSecretSharing::<Fr>::new(0, 5), Err(ShamirError::ZeroThreshold)));
+        assert!(matches!(ShamirSecretSharing::<Fr>::new(6, 5), Err(ShamirError::ThresholdExceedsShares { threshold: 6, shares: 5 })));
+        assert!(matches!(ShamirSecretSharing::<Fr>::with_indices(2, vec![Fr::from(0), Fr::from(1)]), Err(ShamirError::ZeroIndex)));
+        assert!(matches!(ShamirSecretSharing::<Fr>::with_indices(2, vec![Fr::from(7), Fr::from(7)]), Err(ShamirError::DuplicateIndex)));
+    }
+
+    #[test]
+    fn test_custom_indices() {
+        let sss = ShamirSecretSharing::<Fr>::with_indices(2, vec![Fr::from(10), Fr::from(20), -Fr::from(3)]).unwrap();
+        let secret = Fr::from(99u64);
+        let shares = sss.split_secret(secret);
+
+        assert_eq!(shares.iter().map(|share| share.index).collect::<Vec<_>>(), sss.indices());
+        assert_eq!(ShamirSecretSharing::reconstruct_secret(&shares[1..]), secret);
+    }
+
+    #[test]
+    fn test_byte_secrets() {
+        let sss = ShamirSecretSharing::<Fr>::new(3, 5).unwrap();
+
+        for secret in [b"".to_vec(), b"\x00\x00leading zeros".to_vec(), vec![0xab; 100]] {
+            let shares = sss.split_bytes(&secret);
+
+            assert_eq!(ShamirSecretSharing::reconstruct_bytes(&shares[2..]), Some(secret));
+        }
+    }
+
+    #[test]
+    fn test_robust_reconstruction() {
+        let sss = ShamirSecretSharing::<Fr>::new(3, 7).unwrap();
+        let secret = Fr::from(123456u64);
+        let mut shares = sss.split_secret(secret);
+
+        shares[1].value += Fr::from(1);
+        shares[5].value = Fr::from(0);
+
+        // plain interpolation of the first shares is silently wrong
+        assert_ne!(ShamirSecretSharing::reconstruct_secret(&shares[..3]), secret);
+
+        assert_eq!(sss.reconstruct_secret_robust(&shares), Some((secret, vec![Fr::from(2), Fr::from(6)])));
+    }
+
+    fn subset(shares: &[Share<Fr>], positions: &[usize]) -> Vec<Share<Fr>> {
+        positions.iter().map(|position| shares[*position]).collect()
+    }
+
+    #[test]
+    fn test_refresh_shares() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let sss = ShamirSecretSharing::<Fr>::new(3, 5).unwrap();
+        let secret = Fr::from(123456u64);
+        let shares = sss.split_secret(secret);
+
+        let refreshed = sss.refresh_shares(&shares, &mut rng);
+
+        assert!(shares.iter().zip(refreshed.iter()).all(|(share, new_share)| share.index == new_share.index && share.value != new_share.value));
+        assert_eq!(ShamirSecretSharing::reconstruct_secret(&subset(&refreshed, &[1, 3, 4])), secret);
+
+        // two old shares and one new share do not give the secret
+        let mixed = vec![shares[0], shares[1], refreshed[2]];
+        assert_ne!(ShamirSecretSharing::reconstruct_secret(&mixed), secret);
+    }
+
+    #[test]
+    fn test_reshare() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let sss = ShamirSecretSharing::<Fr>::new(3, 5).unwrap();
+        let new_sss = ShamirSecretSharing::<Fr>::new(4, 7).unwrap();
+        let secret = Fr::from(123456u64);
+        let shares = sss.split_secret(secret);
+
+        assert_eq!(sss.reshare(&shares[..2], &new_sss, &mut rng), None);
+
+        let new_shares = sss.reshare(&subset(&shares, &[0, 2, 4]), &new_sss, &mut rng).unwrap();
+
+        assert_eq!(new_shares.len(), 7);
+        assert_eq!(ShamirSecretSharing::reconstruct_secret(&subset(&new_shares, &[1, 2, 5, 6])), secret);
+        assert_ne!(ShamirSecretSharing::reconstruct_secret(&subset(&new_shares, &[1, 2, 5])), secret);
+
+        let mixed = vec![shares[0], shares[1], shares[2], new_shares[3]];
+        assert_ne!(ShamirSecretSharing::reconstruct_secret(&mixed), secret);
+    }
+}
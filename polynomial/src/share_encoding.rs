@@ -0,0 +1,156 @@
+use ark_ff::{BigInteger, PrimeField};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use sha2::{Digest, Sha256};
+
+use crate::shamirr_secret_sharing::Share;
+
+pub const VERSION: u8 = 1;
+const CHECKSUM_SIZE: usize = 4;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ShareDecodingError {
+    // the text is not valid base64 or hex
+    InvalidText,
+    Malformed,
+    BadChecksum,
+    UnsupportedVersion(u8),
+    // the share belongs to another field
+    WrongField
+}
+
+// A share as handed to its holder, together with what is needed to use it.
+//
+// Binary layout, with the integers and field elements in ark-serialize's compressed encoding:
+//     version: u8 || field id: [u8; 4] || threshold: u32 || index: F || value: F || checksum: [u8; 4]
+// The field id is the start of SHA-256 of the modulus and the checksum the start of SHA-256 of
+// everything before it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EncodedShare<F: PrimeField> {
+    pub threshold: u32,
+    pub share: Share<F>
+}
+
+#[derive(CanonicalSerialize, CanonicalDeserialize)]
+struct Payload<F: PrimeField> {
+    version: u8,
+    field_id: [u8; 4],
+    threshold: u32,
+    index: F,
+    value: F
+}
+
+impl <F: PrimeField> EncodedShare<F> {
+    pub fn new(threshold: u32, share: Share<F>) -> Self {
+        Self { threshold, share }
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let payload = Payload { version: VERSION, field_id: field_id::<F>(), threshold: self.threshold, index: self.share.index, value: self.share.value };
+
+        let mut bytes = Vec::new();
+        payload.serialize_compressed(&mut bytes).expect("serializing into a vector cannot fail");
+        let checksum = checksum(&bytes);
+        bytes.extend(checksum);
+
+        bytes
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, ShareDecodingError> {
+        if bytes.len() < CHECKSUM_SIZE {
+            return Err(ShareDecodingError::Malformed);
+        }
+
+        let (body, expected_checksum) = bytes.split_at(bytes.len() - CHECKSUM_SIZE);
+        if checksum(body) != expected_checksum {
+            return Err(ShareDecodingError::BadChecksum);
+        }
+
+        // the version and field id are read first, so a share for another field is reported as such
+        match body.first() {
+            Some(&VERSION) => {},
+            Some(version) => return Err(ShareDecodingError::UnsupportedVersion(*version)),
+            None => return Err(ShareDecodingError::Malformed)
+        }
+        if body.get(1..5) != Some(field_id::<F>().as_slice()) {
+            return Err(ShareDecodingError::WrongField);
+        }
+
+        let mut reader = body;
+        let payload = Payload::<F>::deserialize_compressed(&mut reader).map_err(|_| ShareDecodingError::Malformed)?;
+        if !reader.is_empty() {
+            return Err(ShareDecodingError::Malformed);
+        }
+
+        Ok(Self { threshold: payload.threshold, share: Share { index: payload.index, value: payload.value } })
+    }
+
+    pub fn to_base64(&self) -> String {
+        STANDARD.encode(self.to_bytes())
+    }
+
+    pub fn from_base64(text: &str) -> Result<Self, ShareDecodingError> {
+        Self::from_bytes(&STANDARD.decode(text.trim()).map_err(|_| ShareDecodingError::InvalidText)?)
+    }
+
+    pub fn to_hex(&self) -> String {
+        hex::encode(self.to_bytes())
+    }
+
+    pub fn from_hex(text: &str) -> Result<Self, ShareDecodingError> {
+        Self::from_bytes(&hex::decode(text.trim()).map_err(|_| ShareDecodingError::InvalidText)?)
+    }
+}
+
+fn field_id<F: PrimeField>() -> [u8; 4] {
+    let digest = Sha256::digest(F::MODULUS.to_bytes_be());
+
+    [digest[0], digest[1], digest[2], digest[3]]
+}
+
+fn checksum(bytes: &[u8]) -> [u8; CHECKSUM_SIZE] {
+    let digest = Sha256::digest(bytes);
+
+    [digest[0], digest[1], digest[2], digest[3]]
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shamirr_secret_sharing::ShamirSecretSharing;
+    use ark_bn254::Fr;
+
+    #[test]
+    fn test_share_encoding_roundtrip() {
+        let sss = ShamirSecretSharing::<Fr>::new(3, 5).unwrap();
+        let shares = sss.split_secret(Fr::from(123456u64));
+        let encoded = EncodedShare::new(3, shares[2]);
+
+        // 1 + 4 + 4 + 32 + 32 + 4
+        assert_eq!(encoded.to_bytes().len(), 77);
+        assert_eq!(EncodedShare::from_bytes(&encoded.to_bytes()), Ok(encoded));
+        assert_eq!(EncodedShare::from_base64(&encoded.to_base64()), Ok(encoded));
+        assert_eq!(EncodedShare::from_hex(&encoded.to_hex()), Ok(encoded));
+    }
+
+    #[test]
+    fn test_share_decoding_errors() {
+        let encoded = EncodedShare::new(2, Share { index: Fr::from(1), value: Fr::from(42) });
+        let bytes = encoded.to_bytes();
+
+        let mut corrupted = bytes.clone();
+        corrupted[40] ^= 1;
+        assert_eq!(EncodedShare::<Fr>::from_bytes(&corrupted), Err(ShareDecodingError::BadChecksum));
+
+        assert_eq!(EncodedShare::<ark_bls12_381::Fr>::from_bytes(&bytes), Err(ShareDecodingError::WrongField));
+        assert_eq!(EncodedShare::<Fr>::from_hex("not hex"), Err(ShareDecodingError::InvalidText));
+        assert_eq!(EncodedShare::<Fr>::from_bytes(&bytes[..3]), Err(ShareDecodingError::Malformed));
+        assert_eq!(EncodedShare::<Fr>::from_bytes(&bytes[..40]), Err(ShareDecodingError::BadChecksum));
+
+        let mut future_version = bytes[..bytes.len() - CHECKSUM_SIZE].to_vec();
+        future_version[0] = 2;
+        future_version.extend(checksum(&future_version));
+        assert_eq!(EncodedShare::<Fr>::from_bytes(&future_version), Err(ShareDecodingError::UnsupportedVersion(2)));
+    }
+}
@@ -0,0 +1,181 @@
+use ark_bls12_381::{g2, Bls12_381, Fr, G1Affine, G1Projective, G2Affine, G2Projective};
+use ark_ec::hashing::{curve_maps::wb::WBMap, map_to_curve_hasher::MapToCurveBasedHasher, HashToCurve};
+use ark_ec::pairing::Pairing;
+use ark_ec::{AffineRepr, CurveGroup, PrimeGroup};
+use ark_ff::{field_hashers::DefaultFieldHasher, UniformRand, Zero};
+use rand::Rng;
+use sha2::Sha256;
+
+use crate::dkg::DkgOutput;
+use crate::interpolation;
+use crate::shamirr_secret_sharing::{ShamirSecretSharing, Share};
+
+// ciphersuite of the IETF BLS draft for public keys in G1 and signatures in G2
+pub const DST: &[u8] = b"BLS_SIG_BLS12381G2_XMD:SHA-256_SSWU_RO_NUL_";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ThresholdBlsError {
+    NotEnoughPartials { got: usize, required: usize },
+    // two partial signatures from the same signer
+    DuplicateIndex
+}
+
+// What a signer holds: its share of the secret key, and what everyone knows about the key.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SignerKey {
+    pub share: Share<Fr>,
+    pub public_key: G1Affine
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PartialSignature {
+    pub index: Fr,
+    pub signature: G2Affine
+}
+
+// The public side of a threshold key: the group public key g^x and every signer's
+// verification key g^{x_i}, against which partial signatures are checked.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PublicKeySet {
+    pub threshold: usize,
+    pub public_key: G1Affine,
+    pub verification_keys: Vec<(Fr, G1Affine)>
+}
+
+// Threshold BLS: the secret key x is Shamir shared and signer i signs with its share, H(m)^{x_i}.
+// Since x = sum_i l_i x_i for the Lagrange coefficients at 0 of any threshold signers,
+// prod_i (H(m)^{x_i})^{l_i} = H(m)^x, the ordinary BLS signature under g^x.
+//
+// Keys come from a trusted dealer, or from the DKG when nobody may know x.
+pub fn deal_keys<R: Rng>(sss: &ShamirSecretSharing<Fr>, rng: &mut R) -> (PublicKeySet, Vec<SignerKey>) {
+    let secret_key = Fr::rand(rng);
+    let public_key = (G1Projective::generator() * secret_key).into_affine();
+
+    let shares = sss.evaluate_shares(&sss.random_coefficients(secret_key, rng));
+    let verification_keys = shares.iter().map(|share| (share.index, (G1Projective::generator() * share.value).into_affine())).collect();
+
+    let signer_keys = shares.into_iter().map(|share| SignerKey { share, public_key }).collect();
+
+    (PublicKeySet { threshold: sss.threshold(), public_key, verification_keys }, signer_keys)
+}
+
+// the key set and the signer's key from a finished DKG run, indices are those of the parties
+pub fn keys_from_dkg(output: &DkgOutput<G1Projective>, num_parties: usize) -> (PublicKeySet, SignerKey) {
+    let verification_keys = (1..=num_parties as u64).map(Fr::from).map(|index| (index, output.verification_key(index))).collect();
+    let public_keys = PublicKeySet { threshold: output.commitments.len(), public_key: output.public_key(), verification_keys };
+
+    (public_keys, SignerKey { share: output.share, public_key: output.public_key() })
+}
+
+pub fn hash_to_g2(message: &[u8]) -> G2Affine {
+    let hasher = MapToCurveBasedHasher::<G2Projective, DefaultFieldHasher<Sha256, 128>, WBMap<g2::Config>>::new(DST)
+        .expect("the domain separation tag is valid");
+
+    hasher.hash(message).expect("hashing to the curve cannot fail for a valid tag")
+}
+
+pub fn sign_partial(key: &SignerKey, message: &[u8]) -> PartialSignature {
+    PartialSignature { index: key.share.index, signature: (hash_to_g2(message) * key.share.value).into_affine() }
+}
+
+impl PublicKeySet {
+    pub fn verification_key(&self, index: Fr) -> Option<G1Affine> {
+        self.verification_keys.iter().find(|(signer, _)| *signer == index).map(|(_, key)| *key)
+    }
+
+    // a partial signature is a BLS signature under the signer's verification key
+    pub fn verify_partial(&self, message: &[u8], partial: &PartialSignature) -> bool {
+        self.verification_key(partial.index).is_some_and(|key| verify(&key, message, &partial.signature))
+    }
+
+    // Lagrange interpolation at 0 in the exponent of the first threshold partial signatures.
+    // The partials are not checked here, pass them through verify_partial first when they are untrusted.
+    pub fn combine(&self, partials: &[PartialSignature]) -> Result<G2Affine, ThresholdBlsError> {
+        if partials.len() < self.threshold {
+            return Err(ThresholdBlsError::NotEnoughPartials { got: partials.len(), required: self.threshold });
+        }
+
+        let partials = &partials[..self.threshold];
+        let indices: Vec<Fr> = partials.iter().map(|partial| partial.index).collect();
+        if indices.iter().enumerate().any(|(i, index)| indices[i + 1..].contains(index)) {
+            return Err(ThresholdBlsError::DuplicateIndex);
+        }
+
+        let coefficients = interpolation::lagrange_coefficients(&indices, Fr::zero());
+        let signature: G2Projective = partials.iter().zip(coefficients).map(|(partial, coefficient)| partial.signature * coefficient).sum();
+
+        Ok(signature.into_affine())
+    }
+
+    // combines only the partials that verify
+    pub fn combine_verified(&self, message: &[u8], partials: &[PartialSignature]) -> Result<G2Affine, ThresholdBlsError> {
+        let valid: Vec<PartialSignature> = partials.iter().filter(|partial| self.verify_partial(message, partial)).copied().collect();
+
+        self.combine(&valid)
+    }
+}
+
+// e(g1, signature) == e(public_key, H(m))
+pub fn verify(public_key: &G1Affine, message: &[u8], signature: &G2Affine) -> bool {
+    if public_key.is_zero() {
+        return false;
+    }
+
+    // the target group is written additively, so a product of pairings equal to one is zero here
+    Bls12_381::multi_pairing([-G1Affine::generator(), *public_key], [*signature, hash_to_g2(message)]).is_zero()
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dkg::run_dkg;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    #[test]
+    fn test_threshold_signature() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let (public_keys, signer_keys) = deal_keys(&ShamirSecretSharing::new(3, 5).unwrap(), &mut rng);
+        let message = b"threshold bls";
+
+        let partials: Vec<PartialSignature> = signer_keys.iter().map(|key| sign_partial(key, message)).collect();
+        assert!(partials.iter().all(|partial| public_keys.verify_partial(message, partial)));
+
+        // any three signers produce the same signature
+        let signature = public_keys.combine(&partials[..3]).unwrap();
+        assert!(verify(&public_keys.public_key, message, &signature));
+        assert_eq!(public_keys.combine(&partials[2..]), Ok(signature));
+        assert!(!verify(&public_keys.public_key, b"another message", &signature));
+
+        assert_eq!(public_keys.combine(&partials[..2]), Err(ThresholdBlsError::NotEnoughPartials { got: 2, required: 3 }));
+        assert_eq!(public_keys.combine(&[partials[0], partials[0], partials[1]]), Err(ThresholdBlsError::DuplicateIndex));
+    }
+
+    #[test]
+    fn test_bad_partial_is_skipped() {
+        let mut rng = StdRng::seed_from_u64(1);
+        let (public_keys, signer_keys) = deal_keys(&ShamirSecretSharing::new(2, 4).unwrap(), &mut rng);
+        let message = b"threshold bls";
+
+        let mut partials: Vec<PartialSignature> = signer_keys.iter().map(|key| sign_partial(key, message)).collect();
+        partials[0].signature = (partials[0].signature + G2Affine::generator()).into_affine();
+
+        assert!(!public_keys.verify_partial(message, &partials[0]));
+        assert!(!verify(&public_keys.public_key, message, &public_keys.combine(&partials).unwrap()));
+        assert!(verify(&public_keys.public_key, message, &public_keys.combine_verified(message, &partials).unwrap()));
+    }
+
+    #[test]
+    fn test_keys_from_dkg() {
+        let mut rng = StdRng::seed_from_u64(2);
+        let outputs = run_dkg::<G1Projective, _>(3, 5, &mut rng).unwrap();
+        let message = b"threshold bls";
+
+        let keys: Vec<(PublicKeySet, SignerKey)> = outputs.iter().map(|output| keys_from_dkg(output, 5)).collect();
+        let public_keys = &keys[0].0;
+
+        let partials: Vec<PartialSignature> = keys[1..4].iter().map(|(_, key)| sign_partial(key, message)).collect();
+        assert!(partials.iter().all(|partial| public_keys.verify_partial(message, partial)));
+        assert!(verify(&public_keys.public_key, message, &public_keys.combine(&partials).unwrap()));
+    }
+}
@@ -0,0 +1,232 @@
+use ark_ec::{AffineRepr, CurveGroup};
+use ark_ff::{PrimeField, UniformRand};
+use rand::Rng;
+use sha2::{Digest, Sha256};
+
+use crate::shamirr_secret_sharing::{ShamirSecretSharing, Share};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum VssError<F: PrimeField> {
+    // fewer valid shares than the threshold, with the indices of the shares that failed verification
+    NotEnoughValidShares { valid: usize, required: usize, invalid: Vec<F> },
+    // one commitment per coefficient: more would hide a polynomial of higher degree than the threshold allows
+    WrongCommitmentCount { got: usize, expected: usize }
+}
+
+// Feldman VSS: the dealer publishes g^{a_j} for every coefficient a_j of the sharing polynomial,
+// so share i can be checked against g^{f(i)} = prod_j (g^{a_j})^{i^j}. The commitment to a_0 = secret
+// reveals g^secret, use Pedersen VSS when that is too much.
+pub struct FeldmanVSS<G: CurveGroup> {
+    sss: ShamirSecretSharing<G::ScalarField>
+}
+
+pub struct FeldmanDealing<G: CurveGroup> {
+    pub shares: Vec<Share<G::ScalarField>>,
+    pub commitments: Vec<G::Affine>
+}
+
+impl <G: CurveGroup> FeldmanVSS<G> {
+    pub fn new(sss: ShamirSecretSharing<G::ScalarField>) -> Self {
+        Self { sss }
+    }
+
+    pub fn deal<R: Rng>(&self, secret: G::ScalarField, rng: &mut R) -> FeldmanDealing<G> {
+        let coefficients = self.sss.random_coefficients(secret, rng);
+        let commitments = G::normalize_batch(&coefficients.iter().map(|coefficient| G::generator() * coefficient).collect::<Vec<_>>());
+
+        FeldmanDealing { shares: self.sss.evaluate_shares(&coefficients), commitments }
+    }
+
+    pub fn verify_share(&self, commitments: &[G::Affine], share: &Share<G::ScalarField>) -> bool {
+        commitments.len() == self.sss.threshold() && G::generator() * share.value == evaluate_commitments::<G>(commitments, share.index)
+    }
+
+    // reconstructs from the shares that verify, ignoring the others
+    pub fn reconstruct(&self, commitments: &[G::Affine], shares: &[Share<G::ScalarField>]) -> Result<G::ScalarField, VssError<G::ScalarField>> {
+        check_commitment_count(commitments, self.sss.threshold())?;
+        let (valid, invalid): (Vec<_>, Vec<_>) = shares.iter().copied().partition(|share| self.verify_share(commitments, share));
+
+        reconstruct_from(&valid, &invalid, self.sss.threshold())
+    }
+}
+
+// Pedersen VSS: a second random polynomial b blinds the commitments g^{a_j} h^{b_j}, which then reveal
+// nothing about the secret. Every holder receives (f(i), b(i)). The discrete log of h to base g must be
+// unknown, so h is hashed to the curve rather than chosen.
+pub struct PedersenVSS<G: CurveGroup> {
+    sss: ShamirSecretSharing<G::ScalarField>,
+    pub h: G::Affine
+}
+
+// a share of the secret together with the share of the blinding polynomial at the same index
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PedersenShare<F: PrimeField> {
+    pub share: Share<F>,
+    pub blinding: F
+}
+
+pub struct PedersenDealing<G: CurveGroup> {
+    pub shares: Vec<PedersenShare<G::ScalarField>>,
+    pub commitments: Vec<G::Affine>
+}
+
+impl <G: CurveGroup> PedersenVSS<G> {
+    pub fn new(sss: ShamirSecretSharing<G::ScalarField>) -> Self {
+        Self { sss, h: hash_to_generator::<G>(b"pedersen-vss-h").into_affine() }
+    }
+
+    pub fn deal<R: Rng>(&self, secret: G::ScalarField, rng: &mut R) -> PedersenDealing<G> {
+        let coefficients = self.sss.random_coefficients(secret, rng);
+        let blinding = self.sss.random_coefficients(G::ScalarField::rand(rng), rng);
+
+        let commitments = G::normalize_batch(
+            &coefficients.iter().zip(blinding.iter()).map(|(a, b)| G::generator() * a + self.h * b).collect::<Vec<_>>()
+        );
+
+        let shares = self.sss.evaluate_shares(&coefficients).into_iter()
+            .zip(self.sss.evaluate_shares(&blinding))
+            .map(|(share, blinding_share)| PedersenShare { share, blinding: blinding_share.value })
+            .collect();
+
+        PedersenDealing { shares, commitments }
+    }
+
+    pub fn verify_share(&self, commitments: &[G::Affine], share: &PedersenShare<G::ScalarField>) -> bool {
+        commitments.len() == self.sss.threshold()
+            && G::generator() * share.share.value + self.h * share.blinding == evaluate_commitments::<G>(commitments, share.share.index)
+    }
+
+    pub fn reconstruct(&self, commitments: &[G::Affine], shares: &[PedersenShare<G::ScalarField>]) -> Result<G::ScalarField, VssError<G::ScalarField>> {
+        check_commitment_count(commitments, self.sss.threshold())?;
+        let (valid, invalid): (Vec<_>, Vec<_>) = shares.iter().partition(|share| self.verify_share(commitments, share));
+        let unblinded = |shares: Vec<&PedersenShare<G::ScalarField>>| shares.iter().map(|share| share.share).collect::<Vec<_>>();
+
+        reconstruct_from(&unblinded(valid), &unblinded(invalid), self.sss.threshold())
+    }
+}
+
+// prod_j C_j^{i^j}, written additively
+pub(crate) fn evaluate_commitments<G: CurveGroup>(commitments: &[G::Affine], x: G::ScalarField) -> G {
+    commitments.iter().rev().fold(G::zero(), |acc, commitment| acc * x + commitment)
+}
+
+fn check_commitment_count<A, F: PrimeField>(commitments: &[A], threshold: usize) -> Result<(), VssError<F>> {
+    if commitments.len() != threshold {
+        return Err(VssError::WrongCommitmentCount { got: commitments.len(), expected: threshold });
+    }
+
+    Ok(())
+}
+
+fn reconstruct_from<F: PrimeField>(valid: &[Share<F>], invalid: &[Share<F>], threshold: usize) -> Result<F, VssError<F>> {
+    if valid.len() < threshold {
+        let invalid = invalid.iter().map(|share| share.index).collect();
+        return Err(VssError::NotEnoughValidShares { valid: valid.len(), required: threshold, invalid });
+    }
+
+    Ok(ShamirSecretSharing::reconstruct_secret(&valid[..threshold]))
+}
+
+// Try-and-increment: hash the seed with a counter until the digest is the x-coordinate of a point,
+// then clear the cofactor so the result is in the prime order subgroup.
+pub fn hash_to_generator<G: CurveGroup>(seed: &[u8]) -> G {
+    for counter in 0u32.. {
+        let bytes: Vec<u8> = (0u8..2)
+            .flat_map(|block| Sha256::new().chain_update(seed).chain_update(counter.to_be_bytes()).chain_update([block]).finalize())
+            .collect();
+
+        if let Some(point) = G::Affine::from_random_bytes(&bytes) {
+            let point = point.clear_cofactor();
+            if !point.is_zero() {
+                return point.into_group();
+            }
+        }
+    }
+
+    unreachable!("no point found for any counter")
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_ec::PrimeGroup;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    #[test]
+    fn test_feldman_vss() {
+        type G = ark_bn254::G1Projective;
+        let mut rng = StdRng::seed_from_u64(0);
+
+        let vss = FeldmanVSS::<G>::new(ShamirSecretSharing::new(3, 5).unwrap());
+        let secret = ark_bn254::Fr::from(123456u64);
+        let mut dealing = vss.deal(secret, &mut rng);
+
+        assert!(dealing.shares.iter().all(|share| vss.verify_share(&dealing.commitments, share)));
+        assert_eq!(dealing.commitments[0], (G::generator() * secret).into_affine());
+
+        // one corrupted share is detected and left out
+        dealing.shares[1].value += ark_bn254::Fr::from(1);
+        assert!(!vss.verify_share(&dealing.commitments, &dealing.shares[1]));
+        assert_eq!(vss.reconstruct(&dealing.commitments, &dealing.shares), Ok(secret));
+
+        // three corrupted shares leave too few to reconstruct
+        dealing.shares[3].value += ark_bn254::Fr::from(1);
+        dealing.shares[4].value += ark_bn254::Fr::from(1);
+        assert_eq!(
+            vss.reconstruct(&dealing.commitments, &dealing.shares),
+            Err(VssError::NotEnoughValidShares { valid: 2, required: 3, invalid: vec![2, 4, 5].into_iter().map(ark_bn254::Fr::from).collect() })
+        );
+    }
+
+    #[test]
+    fn test_pedersen_vss() {
+        type G = ark_bls12_381::G1Projective;
+        let mut rng = StdRng::seed_from_u64(0);
+
+        let vss = PedersenVSS::<G>::new(ShamirSecretSharing::new(2, 4).unwrap());
+        let secret = ark_bls12_381::Fr::from(42u64);
+        let mut dealing = vss.deal(secret, &mut rng);
+
+        assert!(dealing.shares.iter().all(|share| vss.verify_share(&dealing.commitments, share)));
+        // the commitment to the secret is blinded
+        assert_ne!(dealing.commitments[0], (G::generator() * secret).into_affine());
+
+        dealing.shares[0].blinding += ark_bls12_381::Fr::from(1);
+        assert!(!vss.verify_share(&dealing.commitments, &dealing.shares[0]));
+        assert_eq!(vss.reconstruct(&dealing.commitments, &dealing.shares), Ok(secret));
+    }
+
+    #[test]
+    fn test_rejects_dealing_of_higher_degree() {
+        type G = ark_bn254::G1Projective;
+        type Fr = ark_bn254::Fr;
+        let mut rng = StdRng::seed_from_u64(1);
+
+        // a dealer commits to a degree 3 polynomial where threshold 3 allows degree 2: every share
+        // lies on the committed polynomial, yet different sets of 3 shares would give different secrets
+        let sss = ShamirSecretSharing::new(3, 5).unwrap();
+        let dealing = FeldmanVSS::<G>::new(ShamirSecretSharing::new(4, 5).unwrap()).deal(Fr::from(7), &mut rng);
+        let vss = FeldmanVSS::<G>::new(sss.clone());
+
+        assert!(!dealing.shares.iter().any(|share| vss.verify_share(&dealing.commitments, share)));
+        assert_eq!(vss.reconstruct(&dealing.commitments, &dealing.shares), Err(VssError::WrongCommitmentCount { got: 4, expected: 3 }));
+
+        let dealing = PedersenVSS::<G>::new(ShamirSecretSharing::new(4, 5).unwrap()).deal(Fr::from(7), &mut rng);
+        let vss = PedersenVSS::<G>::new(sss);
+
+        assert!(!dealing.shares.iter().any(|share| vss.verify_share(&dealing.commitments, share)));
+        assert_eq!(vss.reconstruct(&dealing.commitments, &dealing.shares), Err(VssError::WrongCommitmentCount { got: 4, expected: 3 }));
+    }
+
+    #[test]
+    fn test_hash_to_generator() {
+        type G = ark_bls12_381::G1Projective;
+        let h = hash_to_generator::<G>(b"seed").into_affine();
+
+        assert!(h.is_on_curve() && h.is_in_correct_subgroup_assuming_on_curve());
+        assert_eq!(h, hash_to_generator::<G>(b"seed").into_affine());
+        assert_ne!(h, hash_to_generator::<G>(b"other seed").into_affine());
+        assert_ne!(h, G::generator().into_affine());
+    }
+}
@@ -0,0 +1,105 @@
+use ark_ff::PrimeField;
+use rand::RngCore;
+
+use ark_crypto_primitives::sponge::Absorb;
+
+use super::{poseidon_transcript::PoseidonTranscript, transcript::{HashTrait, Transcript}};
+
+// What the prover and verifier state machines talk through. Prover messages are sent on the channel
+// and the verifier's challenges come out of it, so the same protocol code runs interactively with
+// real verifier randomness or non-interactively with Fiat-Shamir.
+pub trait Channel<F: PrimeField> {
+    fn send(&mut self, label: &[u8], message: &[F]);
+    fn challenge(&mut self, label: &[u8]) -> F;
+
+    fn challenges(&mut self, label: &[u8], n: usize) -> Vec<F> {
+        (0..n).map(|_| self.challenge(label)).collect()
+    }
+}
+
+// Fiat-Shamir: challenges are derived from everything sent so far.
+impl <K: HashTrait, F: PrimeField> Channel<F> for Transcript<K, F> {
+    fn send(&mut self, label: &[u8], message: &[F]) {
+        self.append_field_elements(label, message);
+    }
+
+    fn challenge(&mut self, label: &[u8]) -> F {
+        self.challenge_scalar(label)
+    }
+
+    fn challenges(&mut self, label: &[u8], n: usize) -> Vec<F> {
+        self.challenge_scalars(label, n)
+    }
+}
+
+// Fiat-Shamir over a Poseidon sponge, for verifiers that run inside a circuit over F.
+impl <F: PrimeField + Absorb> Channel<F> for PoseidonTranscript<F> {
+    fn send(&mut self, label: &[u8], message: &[F]) {
+        self.append_field_elements(label, message);
+    }
+
+    fn challenge(&mut self, label: &[u8]) -> F {
+        self.challenge_scalar(label)
+    }
+
+    fn challenges(&mut self, label: &[u8], n: usize) -> Vec<F> {
+        self.challenge_scalars(label, n)
+    }
+}
+
+// Interactive verifier: challenges are fresh randomness that does not depend on the messages,
+// which are only counted. The prover and verifier share one channel.
+pub struct InteractiveChannel<R: RngCore> {
+    rng: R,
+    pub messages_sent: usize
+}
+
+impl <R: RngCore> InteractiveChannel<R> {
+    pub fn new(rng: R) -> Self {
+        Self { rng, messages_sent: 0 }
+    }
+}
+
+impl <F: PrimeField, R: RngCore> Channel<F> for InteractiveChannel<R> {
+    fn send(&mut self, _label: &[u8], _message: &[F]) {
+        self.messages_sent += 1;
+    }
+
+    fn challenge(&mut self, _label: &[u8]) -> F {
+        F::rand(&mut self.rng)
+    }
+}
+
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use ark_bn254::Fq;
+    use rand::{rngs::StdRng, SeedableRng};
+    use sha3::{Digest, Keccak256};
+
+    #[test]
+    fn test_transcript_channel_matches_transcript() {
+        let mut channel = Transcript::<Keccak256, Fq>::new(b"test", Keccak256::new());
+        let mut transcript = Transcript::<Keccak256, Fq>::new(b"test", Keccak256::new());
+
+        channel.send(b"xs", &[Fq::from(1), Fq::from(2)]);
+        transcript.append_field_elements(b"xs", &[Fq::from(1), Fq::from(2)]);
+
+        assert_eq!(Channel::challenge(&mut channel, b"r"), transcript.challenge_scalar(b"r"));
+    }
+
+    #[test]
+    fn test_interactive_channel_ignores_messages() {
+        let mut channel = InteractiveChannel::new(StdRng::seed_from_u64(1));
+        let mut channel1 = InteractiveChannel::new(StdRng::seed_from_u64(1));
+
+        channel.send(b"xs", &[Fq::from(1)]);
+        channel1.send(b"xs", &[Fq::from(2)]);
+
+        let challenges: Vec<Fq> = channel.challenges(b"r", 2);
+        assert_eq!(challenges, channel1.challenges(b"r", 2));
+        assert_ne!(challenges[0], challenges[1]);
+        assert_eq!(channel.messages_sent, 1);
+    }
+}
@@ -4,6 +4,7 @@ use std::marker::PhantomData;
 
 use super::multilinear_polynomial::{self, MultilinearPoly};
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Operator {
     Add,
     Mul
@@ -26,14 +27,30 @@ pub struct Circuit<F: PrimeField> {
     _phantom: PhantomData<F>
 }
 
+#[derive(Debug, Clone)]
 pub struct ProductPoly<F: PrimeField> {
     pub evaluation: Vec<MultilinearPoly<F>>
 }
 
+#[derive(Debug, Clone)]
 pub struct SumPoly<F: PrimeField> {
     pub polys: Vec<ProductPoly<F>>
 }
 
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CircuitStats {
+    pub add_gates: usize,
+    pub mul_gates: usize,
+    pub depth: usize,
+    pub width: usize,
+    pub layer_widths: Vec<usize>,
+    // number of variables of add_i / mul_i for every layer
+    pub predicate_variables: Vec<usize>,
+    // field elements a GKR proof for this circuit carries
+    pub estimated_proof_size: usize,
+    pub estimated_proof_bytes: usize
+}
+
 // Gate Implementation 
 impl Gate {
     pub fn new(left_index: usize, right_index: usize, output_index: usize, operator: Operator) -> Self {
@@ -52,6 +69,28 @@ impl Layer {
             gates
         }
     }
+
+    // the gate outputs of this layer given the values of the layer below
+    pub fn evaluate<F: PrimeField>(&self, current_input: &[F]) -> Vec<F> {
+        let max_output_index = self.gates.iter()
+            .map(|gate| gate.output_index)
+            .max()
+            .unwrap_or(0);
+
+        let mut resultant_evaluations = vec![F::zero(); max_output_index + 1];
+        for gate in self.gates.iter() {
+            let left_index_value = current_input[gate.left_index];
+            let right_index_value = current_input[gate.right_index];
+
+            let current_gate_evaluation = match gate.operator {
+                Operator::Add => left_index_value + right_index_value,
+                Operator::Mul => left_index_value * right_index_value
+            };
+            resultant_evaluations[gate.output_index] = current_gate_evaluation;
+        }
+
+        resultant_evaluations
+    }
 }
 
 // Circuit Implementation 
@@ -70,24 +109,7 @@ impl <F: PrimeField>Circuit<F> {
         let mut reversed_evaluations = Vec::new();
         reversed_evaluations.push(current_input.clone());
         for layer in self.layers.iter().rev() {
-            let max_output_index = layer.gates.iter()
-                .map(|gate| gate.output_index)
-                .max()
-                .unwrap_or(0);
-
-            let mut resultant_evaluations = vec![F::zero(); max_output_index + 1];
-            for gate in layer.gates.iter() {
-                let left_index_value = current_input[gate.left_index];
-                let right_index_value = current_input[gate.right_index];
-
-                let current_gate_evaluation = match gate.operator {
-                    Operator::Add => left_index_value + right_index_value,
-                    Operator::Mul => left_index_value * right_index_value
-                };
-                resultant_evaluations[gate.output_index] = current_gate_evaluation;
-            }
-
-            current_input = resultant_evaluations;
+            current_input = layer.evaluate(&current_input);
             reversed_evaluations.push(current_input.clone());
         }
 
@@ -128,6 +150,105 @@ impl <F: PrimeField>Circuit<F> {
 
         (add_i_polynomial, mul_i_polynomial)
     }
+
+    // number of wires feeding the last layer, taken from the evaluation when there is one
+    pub fn num_inputs(&self) -> usize {
+        if let Some(inputs) = self.layer_evaluations.last() {
+            return inputs.len();
+        }
+
+        self.layers.last()
+            .map(|layer| layer.gates.iter().map(|gate| gate.left_index.max(gate.right_index) + 1).max().unwrap_or(0))
+            .unwrap_or(0)
+    }
+
+    // Renders the circuit as a Graphviz digraph, one cluster per layer.
+    // Wire `w{layer}_{index}` is the output `index` of layer `layer`, the inputs live in layer `layers.len()`.
+    // When `with_values` is set and the circuit has been evaluated, every wire also shows its value.
+    pub fn to_dot(&self, with_values: bool) -> String {
+        let input_layer = self.layers.len();
+        let show_values = with_values && !self.layer_evaluations.is_empty();
+
+        let wire_label = |layer_index: usize, wire_index: usize, name: String| -> String {
+            if show_values {
+                if let Some(value) = self.layer_evaluations.get(layer_index).and_then(|layer| layer.get(wire_index)) {
+                    return format!("{}\\n= {}", name, value);
+                }
+            }
+            name
+        };
+
+        let mut dot = String::from("digraph circuit {\n    rankdir=BT;\n    node [shape=circle];\n");
+
+        for (layer_index, layer) in self.layers.iter().enumerate() {
+            dot.push_str(&format!("    subgraph cluster_layer_{} {{\n        label=\"layer {}\";\n", layer_index, layer_index));
+            for gate in layer.gates.iter() {
+                let symbol = match gate.operator {
+                    Operator::Add => "+",
+                    Operator::Mul => "*"
+                };
+                let label = wire_label(layer_index, gate.output_index, format!("{} [{}]", symbol, gate.output_index));
+                dot.push_str(&format!("        w{}_{} [label=\"{}\"];\n", layer_index, gate.output_index, label));
+            }
+            dot.push_str("    }\n");
+        }
+
+        dot.push_str(&format!("    subgraph cluster_layer_{} {{\n        label=\"input\";\n", input_layer));
+        for input_index in 0..self.num_inputs() {
+            let label = wire_label(input_layer, input_index, format!("in [{}]", input_index));
+            dot.push_str(&format!("        w{}_{} [shape=box, label=\"{}\"];\n", input_layer, input_index, label));
+        }
+        dot.push_str("    }\n");
+
+        for (layer_index, layer) in self.layers.iter().enumerate() {
+            for gate in layer.gates.iter() {
+                dot.push_str(&format!("    w{}_{} -> w{}_{} [label=\"L\"];\n", layer_index + 1, gate.left_index, layer_index, gate.output_index));
+                dot.push_str(&format!("    w{}_{} -> w{}_{} [label=\"R\"];\n", layer_index + 1, gate.right_index, layer_index, gate.output_index));
+            }
+        }
+
+        dot.push_str("}\n");
+        dot
+    }
+
+    pub fn stats(&self) -> CircuitStats {
+        let gates = self.layers.iter().flat_map(|layer| layer.gates.iter());
+        let add_gates = gates.clone().filter(|gate| gate.operator == Operator::Add).count();
+        let mul_gates = gates.filter(|gate| gate.operator == Operator::Mul).count();
+
+        let layer_widths: Vec<usize> = self.layers.iter().map(|layer| layer.gates.len()).collect();
+        let width = layer_widths.iter().copied().chain(std::iter::once(self.num_inputs())).max().unwrap_or(0);
+
+        // variables indexing the wires of every layer and of the inputs, log2 of the padded width
+        let wire_variables: Vec<usize> = layer_widths.iter().copied().chain(std::iter::once(self.num_inputs()))
+            .map(|width| width.next_power_of_two().trailing_zeros() as usize)
+            .collect();
+
+        // add_i and mul_i range over (a, b, c), with a at least one variable as in `num_of_layer_variables`
+        let predicate_variables: Vec<usize> = (0..self.layers.len())
+            .map(|layer_index| wire_variables[layer_index].max(1) + 2 * wire_variables[layer_index + 1])
+            .collect();
+
+        // The prover sends the outputs, then for every layer a sumcheck over the b and c variables of
+        // the layer below (degree 2 round polynomials, three evaluations each) followed by the claims
+        // W(b) and W(c).
+        let outputs = layer_widths.first().copied().unwrap_or(0);
+        let estimated_proof_size = outputs + (0..self.layers.len())
+            .map(|layer_index| 2 * wire_variables[layer_index + 1] * 3 + 2)
+            .sum::<usize>();
+        let field_element_bytes = (F::MODULUS_BIT_SIZE as usize).div_ceil(8);
+
+        CircuitStats {
+            add_gates,
+            mul_gates,
+            depth: self.layers.len(),
+            width,
+            layer_widths,
+            predicate_variables,
+            estimated_proof_size,
+            estimated_proof_bytes: estimated_proof_size * field_element_bytes
+        }
+    }
 }
 
 
@@ -181,7 +302,7 @@ pub  fn tensor_multiplication<F:PrimeField>( w_b: &MultilinearPoly<F>, w_c: &Mul
 }
 
 impl <F: PrimeField> ProductPoly<F> {
-    fn new(evaluations: Vec<Vec<F>>) -> Self {
+    pub fn new(evaluations: Vec<Vec<F>>) -> Self {
        let multi_polys = evaluations.iter().map(|eval| MultilinearPoly::new(eval.to_vec())).collect();
 
         Self {evaluation: multi_polys}
@@ -206,10 +327,13 @@ impl <F: PrimeField> ProductPoly<F> {
     }
 
     fn reduce(&self) -> Vec<F> {
-        let first_poly = &self.evaluation[0].coefficients;
-        let second_poly = &self.evaluation[1].coefficients;
+        let mut result = self.evaluation[0].coefficients.clone();
 
-        first_poly.iter().zip(second_poly.iter()).map(|(a,b)| *a * *b ).collect()
+        for poly in self.evaluation.iter().skip(1) {
+            result = result.iter().zip(poly.coefficients.iter()).map(|(a,b)| *a * *b ).collect();
+        }
+
+        result
     }
 }
 
@@ -230,14 +354,17 @@ impl <F: PrimeField> SumPoly<F> {
     }
 
   pub fn get_degree(&self) -> usize {
-        self.polys[0].get_degree()
+        self.polys.iter().map(|product_poly| product_poly.get_degree()).max().unwrap_or(0)
     }
 
   pub  fn reduce(&self) -> Vec<F> {
-        let first_poly = self.polys[0].reduce();
-        let second_poly = self.polys[1].reduce();
+        let mut result = self.polys[0].reduce();
+
+        for product_poly in self.polys.iter().skip(1) {
+            result = result.iter().zip(product_poly.reduce().iter()).map(|(a, b)| *a + *b).collect();
+        }
 
-        first_poly.iter().zip(second_poly.iter()).map(|(a, b)| *a + *b).collect()
+        result
     } 
 }
 
@@ -408,4 +535,74 @@ mod tests {
         let result = tensor_multiplication(&w_b, &w_c);
         assert_eq!(result.coefficients, vec![Fq::from(12), Fq::from(21), Fq::from(20), Fq::from(35)]);
     }
+
+    #[test]
+    fn test_circuit_to_dot() {
+        let gate1 = Gate::new(0, 1, 0, Operator::Mul);
+        let gate2 = Gate::new(0, 1, 0, Operator::Add);
+        let gate3 = Gate::new(2, 3, 1, Operator::Mul);
+
+        let mut circuit = Circuit::<Fq>::new(vec![Layer::new(vec![gate1]), Layer::new(vec![gate2, gate3])]);
+
+        let dot = circuit.to_dot(true);
+        assert!(dot.starts_with("digraph circuit {"));
+        assert!(dot.contains("w0_0 [label=\"* [0]\"];"));
+        assert!(dot.contains("w1_1 [label=\"* [1]\"];"));
+        assert!(dot.contains("w2_3 [shape=box, label=\"in [3]\"];"));
+        assert!(dot.contains("w2_2 -> w1_1 [label=\"L\"];"));
+        assert!(dot.contains("w1_1 -> w0_0 [label=\"R\"];"));
+
+        circuit.evaluate(vec![Fq::from(2), Fq::from(3), Fq::from(4), Fq::from(5)]);
+
+        let dot = circuit.to_dot(true);
+        assert!(dot.contains("w0_0 [label=\"* [0]\\n= 100\"];"));
+        assert!(dot.contains("w1_0 [label=\"+ [0]\\n= 5\"];"));
+        assert!(dot.contains("w2_1 [shape=box, label=\"in [1]\\n= 3\"];"));
+
+        assert!(!circuit.to_dot(false).contains("= 100"));
+    }
+
+    #[test]
+    fn test_circuit_stats() {
+        let layer0 = Layer::new(vec![Gate::new(0, 1, 0, Operator::Add)]);
+        let layer1 = Layer::new(vec![Gate::new(0, 1, 0, Operator::Add), Gate::new(2, 3, 1, Operator::Mul)]);
+        let layer2 = Layer::new(vec![
+            Gate::new(0, 1, 0, Operator::Add),
+            Gate::new(2, 3, 1, Operator::Mul),
+            Gate::new(4, 5, 2, Operator::Mul),
+            Gate::new(6, 7, 3, Operator::Mul)
+        ]);
+
+        let circuit = Circuit::<Fq>::new(vec![layer0, layer1, layer2]);
+        let stats = circuit.stats();
+
+        assert_eq!(stats.add_gates, 3);
+        assert_eq!(stats.mul_gates, 4);
+        assert_eq!(stats.depth, 3);
+        assert_eq!(stats.width, 8);
+        assert_eq!(stats.layer_widths, vec![1, 2, 4]);
+        assert_eq!(stats.predicate_variables, vec![3, 5, 8]);
+        // 1 output + (2 * 3 + 2) + (4 * 3 + 2) + (6 * 3 + 2)
+        assert_eq!(stats.estimated_proof_size, 43);
+        assert_eq!(stats.estimated_proof_bytes, 43 * 32);
+    }
+
+    #[test]
+    fn test_circuit_stats_non_uniform_widths() {
+        let layer0 = Layer::new(vec![Gate::new(0, 1, 0, Operator::Add)]);
+        let layer1 = Layer::new((0..3).map(|i| Gate::new(2 * i, 2 * i + 1, i, Operator::Mul)).collect());
+        let layer2 = Layer::new((0..6).map(|i| Gate::new(2 * i, 2 * i + 1, i, Operator::Add)).collect());
+
+        let circuit = Circuit::<Fq>::new(vec![layer0, layer1, layer2]);
+        let stats = circuit.stats();
+
+        assert_eq!(stats.layer_widths, vec![1, 3, 6]);
+        assert_eq!(stats.width, 12);
+        // (a, b, c) over 1 + 2 * 2, 2 + 2 * 3 and 3 + 2 * 4 variables
+        assert_eq!(stats.predicate_variables, vec![5, 8, 11]);
+        // the layers below have 3, 6 and 12 wires, so 2, 3 and 4 variables:
+        // 1 output + (4 * 3 + 2) + (6 * 3 + 2) + (8 * 3 + 2)
+        assert_eq!(stats.estimated_proof_size, 61);
+        assert_eq!(stats.estimated_proof_bytes, 61 * 32);
+    }
 }
\ No newline at end of file
@@ -0,0 +1,398 @@
+use ark_ff::PrimeField;
+
+use super::{
+    circuit::{Circuit, Layer, Operator, ProductPoly, SumPoly},
+    multilinear_polynomial::{eq_eval, eq_evaluations, MultilinearPoly},
+    partial_sumcheck::{prove_rounds, verify_rounds},
+    transcript::{HashTrait, Transcript},
+};
+
+// A circuit made of `num_copies` independent copies of the same `template`.
+//
+// The layer values of all the copies are stacked into one table per layer, indexed by (copy, wire)
+// with the copy index in the most significant variables. The wiring predicate of a layer is only ever
+// expressed over the template's wire variables and tied to the copy variables through eq, so the
+// prover works over num_copies * 2^(2 * s) points per layer, linear in the number of copies.
+pub struct DataParallelCircuit<F: PrimeField> {
+    pub template: Circuit<F>,
+    pub num_copies: usize,
+    layer_evaluations: Vec<Vec<F>>
+}
+
+pub struct LayerProof<F: PrimeField> {
+    pub sumcheck_proof: Vec<Vec<F>>,
+    // W_{i+1}(r_copy, r_b) and W_{i+1}(r_copy, r_c) at the end of the layer's sumcheck
+    pub w_b: F,
+    pub w_c: F
+}
+
+// The claim that sum_k weight_k * W_input(point_k) = claim over the stacked inputs, where the
+// copy variables come first in every point.
+pub struct InputClaims<F: PrimeField> {
+    pub claim: F,
+    pub points: Vec<(F, Vec<F>)>
+}
+
+pub struct DataParallelProof<F: PrimeField> {
+    // the stacked (and padded) output layer
+    pub outputs: Vec<F>,
+    pub layer_proofs: Vec<LayerProof<F>>
+}
+
+impl <F: PrimeField> DataParallelCircuit<F> {
+    pub fn new(template: Circuit<F>, num_copies: usize) -> Self {
+        assert!(num_copies.is_power_of_two(), "number of copies must be a power of two");
+
+        Self {
+            template,
+            num_copies,
+            layer_evaluations: Vec::new()
+        }
+    }
+
+    // Evaluates every copy on its own inputs and returns the outputs of every copy.
+    pub fn evaluate(&mut self, inputs: &[Vec<F>]) -> Vec<Vec<F>> {
+        assert!(inputs.len() == self.num_copies, "expected one input vector per copy");
+        assert!(inputs.iter().all(|copy_inputs| copy_inputs.len() == inputs[0].len()), "every copy must have the same number of inputs");
+
+        let mut stacked = vec![Vec::new(); self.template.layers.len() + 1];
+        let mut outputs = Vec::new();
+
+        for copy_inputs in inputs.iter() {
+            outputs.push(self.template.evaluate(copy_inputs.clone()));
+
+            for (layer_index, layer_values) in self.template.layer_evaluations.iter().enumerate() {
+                let mut padded = layer_values.clone();
+                padded.resize(layer_values.len().next_power_of_two(), F::zero());
+                stacked[layer_index].extend(padded);
+            }
+        }
+
+        self.layer_evaluations = stacked;
+
+        outputs
+    }
+
+    pub fn prove<K: HashTrait>(&self, hash_function: K) -> DataParallelProof<F> {
+        assert!(!self.layer_evaluations.is_empty(), "circuit must be evaluated before proving");
+        // every copy fills the same power of two slots of a layer, or the (copy, wire) indexing is off
+        assert!(
+            self.layer_evaluations.iter().all(|layer| layer.len() % self.num_copies == 0 && (layer.len() / self.num_copies).is_power_of_two()),
+            "layer tables are not aligned on the copies"
+        );
+
+        let copy_vars = num_vars(self.num_copies);
+        let outputs = self.layer_evaluations[0].clone();
+
+        let mut transcript = Transcript::new(b"data-parallel-gkr", hash_function);
+        transcript.append_field_elements(b"outputs", &outputs);
+
+        let output_point = transcript.challenge_scalars(b"output_point", num_vars(outputs.len()));
+        let mut copy_point = output_point[..copy_vars].to_vec();
+        let mut wire_points = vec![(F::one(), output_point[copy_vars..].to_vec())];
+
+        let mut layer_proofs = Vec::new();
+
+        for (layer_index, layer) in self.template.layers.iter().enumerate() {
+            let next_layer = &self.layer_evaluations[layer_index + 1];
+            let wire_vars = num_vars(next_layer.len() / self.num_copies);
+
+            let (add_predicate, mul_predicate) = wiring_predicate_tables(layer, &wire_points, wire_vars);
+            let copy_eq = eq_evaluations(&copy_point);
+
+            // tables over (copy, b, c)
+            let size = self.num_copies << (2 * wire_vars);
+            let mut eq_table = Vec::with_capacity(size);
+            let mut add_table = Vec::with_capacity(size);
+            let mut mul_table = Vec::with_capacity(size);
+            let mut w_b_table = Vec::with_capacity(size);
+            let mut w_c_table = Vec::with_capacity(size);
+
+            for copy in 0..self.num_copies {
+                let copy_values = &next_layer[copy << wire_vars..(copy + 1) << wire_vars];
+                for b in 0..1 << wire_vars {
+                    for c in 0..1 << wire_vars {
+                        eq_table.push(copy_eq[copy]);
+                        add_table.push(add_predicate[(b << wire_vars) + c]);
+                        mul_table.push(mul_predicate[(b << wire_vars) + c]);
+                        w_b_table.push(copy_values[b]);
+                        w_c_table.push(copy_values[c]);
+                    }
+                }
+            }
+
+            let layer_poly = SumPoly::new(vec![
+                ProductPoly::new(vec![eq_table.clone(), add_table.clone(), w_b_table.clone()]),
+                ProductPoly::new(vec![eq_table.clone(), add_table, w_c_table.clone()]),
+                ProductPoly::new(vec![eq_table, mul_table, w_b_table, w_c_table])
+            ]);
+
+            let (sumcheck_proof, challenges) = prove_rounds(layer_poly, copy_vars + 2 * wire_vars, &mut transcript);
+
+            let (r_copy, r_b, r_c) = split_challenges(&challenges, copy_vars, wire_vars);
+            let next_poly = MultilinearPoly::new(next_layer.clone());
+            let w_b = next_poly.evaluate(&[r_copy.clone(), r_b.clone()].concat());
+            let w_c = next_poly.evaluate(&[r_copy.clone(), r_c.clone()].concat());
+
+            transcript.append_field_elements(b"layer_claims", &[w_b, w_c]);
+            let combination = transcript.challenge_scalars(b"combination", 2);
+
+            copy_point = r_copy;
+            wire_points = vec![(combination[0], r_b), (combination[1], r_c)];
+
+            layer_proofs.push(LayerProof { sumcheck_proof, w_b, w_c });
+        }
+
+        DataParallelProof { outputs, layer_proofs }
+    }
+
+    // Verifies a proof against the inputs of every copy, only using the template's wiring.
+    pub fn verify<K: HashTrait>(&self, inputs: &[Vec<F>], proof: &DataParallelProof<F>, hash_function: K) -> bool {
+        if inputs.len() != self.num_copies || inputs.iter().any(|copy_inputs| copy_inputs.len() != inputs[0].len()) {
+            return false;
+        }
+
+        let Some(input_claims) = self.reduce_to_input_claims(inputs[0].len(), proof, hash_function) else {
+            return false;
+        };
+
+        // the last claims are checked directly against the stacked inputs
+        let mut stacked_inputs = Vec::new();
+        for copy_inputs in inputs.iter() {
+            let mut padded = copy_inputs.clone();
+            padded.resize(1 << num_vars(inputs[0].len()), F::zero());
+            stacked_inputs.extend(padded);
+        }
+        let input_poly = MultilinearPoly::new(stacked_inputs);
+
+        let expected: F = input_claims.points.iter()
+            .map(|(weight, point)| *weight * input_poly.evaluate(point))
+            .sum();
+
+        expected == input_claims.claim
+    }
+
+    // Checks every layer of the proof and returns what is left to check about the stacked inputs,
+    // for a verifier that does not hold them, e.g. one given a commitment to them instead.
+    // None if a layer does not verify.
+    pub fn reduce_to_input_claims<K: HashTrait>(&self, input_length: usize, proof: &DataParallelProof<F>, hash_function: K) -> Option<InputClaims<F>> {
+        if proof.layer_proofs.len() != self.template.layers.len() {
+            return None;
+        }
+
+        let copy_vars = num_vars(self.num_copies);
+        let output_vars = num_vars(layer_width(&self.template.layers[0]));
+        if proof.outputs.len() != self.num_copies << output_vars {
+            return None;
+        }
+
+        let mut transcript = Transcript::new(b"data-parallel-gkr", hash_function);
+        transcript.append_field_elements(b"outputs", &proof.outputs);
+
+        let output_point = transcript.challenge_scalars(b"output_point", copy_vars + output_vars);
+        let mut claim = MultilinearPoly::new(proof.outputs.clone()).evaluate(&output_point);
+        let mut copy_point = output_point[..copy_vars].to_vec();
+        let mut wire_points = vec![(F::one(), output_point[copy_vars..].to_vec())];
+
+        for (layer_index, (layer, layer_proof)) in self.template.layers.iter().zip(proof.layer_proofs.iter()).enumerate() {
+            let wire_vars = match self.template.layers.get(layer_index + 1) {
+                Some(next_layer) => num_vars(layer_width(next_layer)),
+                None => num_vars(input_length)
+            };
+
+            let (final_claim, challenges) = verify_rounds(claim, &layer_proof.sumcheck_proof, copy_vars + 2 * wire_vars, 4, &mut transcript)?;
+
+            let (r_copy, r_b, r_c) = split_challenges(&challenges, copy_vars, wire_vars);
+            let (add_value, mul_value) = evaluate_wiring_predicate(layer, &wire_points, &r_b, &r_c);
+
+            let expected = eq_eval(&copy_point, &r_copy)
+                * (add_value * (layer_proof.w_b + layer_proof.w_c) + mul_value * layer_proof.w_b * layer_proof.w_c);
+            if expected != final_claim {
+                return None;
+            }
+
+            transcript.append_field_elements(b"layer_claims", &[layer_proof.w_b, layer_proof.w_c]);
+            let combination = transcript.challenge_scalars(b"combination", 2);
+
+            claim = combination[0] * layer_proof.w_b + combination[1] * layer_proof.w_c;
+            copy_point = r_copy;
+            wire_points = vec![(combination[0], r_b), (combination[1], r_c)];
+        }
+
+        let points = wire_points.into_iter().map(|(weight, point)| (weight, [copy_point.clone(), point].concat())).collect();
+
+        Some(InputClaims { claim, points })
+    }
+}
+
+// number of variables needed to index `length` values
+fn num_vars(length: usize) -> usize {
+    length.next_power_of_two().trailing_zeros() as usize
+}
+
+// number of wires a layer outputs, as `Circuit::evaluate` sizes them
+fn layer_width(layer: &Layer) -> usize {
+    layer.gates.iter().map(|gate| gate.output_index).max().unwrap_or(0) + 1
+}
+
+fn split_challenges<F: PrimeField>(challenges: &[F], copy_vars: usize, wire_vars: usize) -> (Vec<F>, Vec<F>, Vec<F>) {
+    (
+        challenges[..copy_vars].to_vec(),
+        challenges[copy_vars..copy_vars + wire_vars].to_vec(),
+        challenges[copy_vars + wire_vars..].to_vec()
+    )
+}
+
+// eq(point, index) with `index` read as bits in the same order as the hypercube tables
+fn eq_at_index<F: PrimeField>(point: &[F], index: usize) -> F {
+    point.iter().enumerate().map(|(i, r)| {
+        if (index >> (point.len() - 1 - i)) & 1 == 1 { *r } else { F::one() - r }
+    }).product()
+}
+
+// Tables of sum_k weight_k * add_i(g_k, b, c) and sum_k weight_k * mul_i(g_k, b, c) over (b, c).
+fn wiring_predicate_tables<F: PrimeField>(layer: &Layer, wire_points: &[(F, Vec<F>)], wire_vars: usize) -> (Vec<F>, Vec<F>) {
+    let mut add_table = vec![F::zero(); 1 << (2 * wire_vars)];
+    let mut mul_table = vec![F::zero(); 1 << (2 * wire_vars)];
+
+    for (weight, point) in wire_points.iter() {
+        let output_eq = eq_evaluations(point);
+
+        for gate in layer.gates.iter() {
+            let position = (gate.left_index << wire_vars) + gate.right_index;
+            let value = *weight * output_eq[gate.output_index];
+
+            match gate.operator {
+                Operator::Add => add_table[position] += value,
+                Operator::Mul => mul_table[position] += value
+            }
+        }
+    }
+
+    (add_table, mul_table)
+}
+
+// Evaluates the same combination of predicates at a single (r_b, r_c), in time linear in the number of gates.
+fn evaluate_wiring_predicate<F: PrimeField>(layer: &Layer, wire_points: &[(F, Vec<F>)], r_b: &[F], r_c: &[F]) -> (F, F) {
+    let mut add_value = F::zero();
+    let mut mul_value = F::zero();
+
+    for gate in layer.gates.iter() {
+        let input_eq = eq_at_index(r_b, gate.left_index) * eq_at_index(r_c, gate.right_index);
+        let output_eq: F = wire_points.iter().map(|(weight, point)| *weight * eq_at_index(point, gate.output_index)).sum();
+
+        match gate.operator {
+            Operator::Add => add_value += output_eq * input_eq,
+            Operator::Mul => mul_value += output_eq * input_eq
+        }
+    }
+
+    (add_value, mul_value)
+}
+
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::zk_project::circuit::Gate;
+    use ark_bn254::Fq;
+    use sha3::{Keccak256, Digest};
+
+    // out = (a + b) * (c * d)
+    fn create_template() -> Circuit<Fq> {
+        let layer0 = Layer::new(vec![Gate::new(0, 1, 0, Operator::Mul)]);
+        let layer1 = Layer::new(vec![Gate::new(0, 1, 0, Operator::Add), Gate::new(2, 3, 1, Operator::Mul)]);
+
+        Circuit::new(vec![layer0, layer1])
+    }
+
+    fn create_inputs(num_copies: u64) -> Vec<Vec<Fq>> {
+        (0..num_copies).map(|copy| (1..=4).map(|i| Fq::from(copy * 4 + i)).collect()).collect()
+    }
+
+    #[test]
+    fn test_data_parallel_evaluation() {
+        let mut circuit = DataParallelCircuit::new(create_template(), 4);
+        let outputs = circuit.evaluate(&create_inputs(4));
+
+        assert_eq!(outputs[0], vec![Fq::from(36)]);
+        assert_eq!(outputs[1], vec![Fq::from(11 * 56)]);
+        assert_eq!(outputs[3], vec![Fq::from(27 * 240)]);
+    }
+
+    #[test]
+    fn test_data_parallel_gkr() {
+        let mut circuit = DataParallelCircuit::new(create_template(), 8);
+        let inputs = create_inputs(8);
+        circuit.evaluate(&inputs);
+
+        let proof = circuit.prove(Keccak256::new());
+
+        assert_eq!(proof.outputs.len(), 8);
+        // one copy variable per doubling, plus b and c for the template layer
+        assert_eq!(proof.layer_proofs[0].sumcheck_proof.len(), 3 + 2);
+        assert_eq!(proof.layer_proofs[1].sumcheck_proof.len(), 3 + 4);
+        assert!(circuit.verify(&inputs, &proof, Keccak256::new()));
+    }
+
+    #[test]
+    fn test_data_parallel_gkr_deeper_template() {
+        let layer0 = Layer::new(vec![Gate::new(0, 1, 0, Operator::Add), Gate::new(0, 1, 1, Operator::Mul)]);
+        let layer1 = Layer::new(vec![Gate::new(0, 1, 0, Operator::Add), Gate::new(2, 3, 1, Operator::Mul)]);
+        let layer2 = Layer::new(vec![
+            Gate::new(0, 1, 0, Operator::Add),
+            Gate::new(2, 3, 1, Operator::Mul),
+            Gate::new(4, 5, 2, Operator::Mul),
+            Gate::new(6, 7, 3, Operator::Mul)
+        ]);
+
+        let mut circuit = DataParallelCircuit::new(Circuit::<Fq>::new(vec![layer0, layer1, layer2]), 2);
+        let inputs: Vec<Vec<Fq>> = (0..2u64).map(|copy| (1..=8).map(|i| Fq::from(copy + i)).collect()).collect();
+        circuit.evaluate(&inputs);
+
+        let proof = circuit.prove(Keccak256::new());
+        assert!(circuit.verify(&inputs, &proof, Keccak256::new()));
+    }
+
+    #[test]
+    fn test_data_parallel_gkr_rejects_wrong_output() {
+        let mut circuit = DataParallelCircuit::new(create_template(), 4);
+        let inputs = create_inputs(4);
+        circuit.evaluate(&inputs);
+
+        let mut proof = circuit.prove(Keccak256::new());
+        proof.outputs[2] += Fq::from(1);
+
+        assert!(!circuit.verify(&inputs, &proof, Keccak256::new()));
+    }
+
+    #[test]
+    fn test_data_parallel_gkr_rejects_wrong_inputs() {
+        let mut circuit = DataParallelCircuit::new(create_template(), 4);
+        let mut inputs = create_inputs(4);
+        circuit.evaluate(&inputs);
+
+        let proof = circuit.prove(Keccak256::new());
+        inputs[1][3] += Fq::from(1);
+
+        assert!(!circuit.verify(&inputs, &proof, Keccak256::new()));
+
+        // a copy of another length is rejected even if padding or truncating it would match
+        inputs[1][3] -= Fq::from(1);
+        inputs[1].push(Fq::from(0));
+
+        assert!(!circuit.verify(&inputs, &proof, Keccak256::new()));
+        inputs[1].pop();
+        assert!(circuit.verify(&inputs, &proof, Keccak256::new()));
+    }
+
+    #[test]
+    #[should_panic(expected = "every copy must have the same number of inputs")]
+    fn test_data_parallel_gkr_rejects_ragged_inputs() {
+        let mut circuit = DataParallelCircuit::new(create_template(), 4);
+        let mut inputs = create_inputs(4);
+        inputs[2].push(Fq::from(0));
+
+        circuit.evaluate(&inputs);
+    }
+}
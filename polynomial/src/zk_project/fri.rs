@@ -0,0 +1,334 @@
+use ark_ff::PrimeField;
+use univariate::{ntt::NttDomain, univariate::UnivariateDense};
+
+use super::{
+    merkle_tree::{MerkleTree, MultiProof},
+    transcript::{HashTrait, Transcript},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FriConfig {
+    // ratio of the evaluation domain to the degree bound, a power of two
+    pub blowup: usize,
+    pub num_queries: usize,
+    // number of points folded into one per round, a power of two
+    pub arity: usize
+}
+
+impl Default for FriConfig {
+    fn default() -> Self {
+        Self { blowup: 4, num_queries: 32, arity: 2 }
+    }
+}
+
+// The cosets opened in one layer with a single multiproof, leaves given as (leaf index, coset values).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LayerOpening<F: PrimeField> {
+    pub leaves: Vec<(usize, Vec<F>)>,
+    pub proof: MultiProof
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FriProof<F: PrimeField> {
+    // one Merkle root per layer, roots[0] commits to the evaluations being tested
+    pub roots: Vec<Vec<u8>>,
+    // the last folded polynomial, sent in the clear
+    pub final_coefficients: Vec<F>,
+    pub openings: Vec<LayerOpening<F>>
+}
+
+// FRI proximity test: the evaluations of f over the domain are close to a polynomial of degree
+// below the degree bound.
+//
+// Writing f(X) = sum_{j < a} X^j f_j(X^a) for arity a, a round folds f into
+// f'(Y) = sum_j beta^j f_j(Y) over the domain raised to the a-th power, which is a times smaller,
+// and divides the degree bound by a. The a points x * zeta^k with the same x^a are stored as one
+// Merkle leaf, so one opening gives everything needed to fold at y = x^a. The verifier checks for
+// random positions that every layer folds into the next one and that the last one agrees with the
+// final polynomial.
+pub struct Fri<F: PrimeField> {
+    pub config: FriConfig,
+    pub degree_bound: usize,
+    pub domain: NttDomain<F>
+}
+
+impl <F: PrimeField> Fri<F> {
+    // None when the blowup or arity is not a power of two of at least 2, or when the field has no
+    // domain of size degree bound * blowup
+    pub fn new(degree_bound: usize, config: FriConfig) -> Option<Self> {
+        if config.blowup < 2 || !config.blowup.is_power_of_two() || config.arity < 2 || !config.arity.is_power_of_two() {
+            return None;
+        }
+
+        let degree_bound = degree_bound.max(1).next_power_of_two();
+        let domain = NttDomain::new(degree_bound * config.blowup)?;
+
+        Some(Self { config, degree_bound, domain })
+    }
+
+    // the number of folding rounds: at least one, and until the degree bound is at most the arity
+    pub fn num_layers(&self) -> usize {
+        let mut degree_bound = self.degree_bound;
+        let mut layers = 0;
+        loop {
+            layers += 1;
+            degree_bound = degree_bound.div_ceil(self.config.arity);
+            if degree_bound <= self.config.arity {
+                return layers;
+            }
+        }
+    }
+
+    pub fn prove_polynomial<K: HashTrait + Default>(&self, poly: &UnivariateDense<F>, transcript: &mut Transcript<K, F>) -> FriProof<F> {
+        self.prove(&self.domain.fft(&poly.coefficients), transcript)
+    }
+
+    pub fn prove<K: HashTrait + Default>(&self, evaluations: &[F], transcript: &mut Transcript<K, F>) -> FriProof<F> {
+        assert!(evaluations.len() == self.domain.size, "evaluations must cover the domain");
+
+        let arity = self.config.arity;
+        let mut values = evaluations.to_vec();
+        let mut generator = self.domain.generator;
+        let mut degree_bound = self.degree_bound;
+        let mut layers = Vec::new();
+
+        for _ in 0..self.num_layers() {
+            let leaves = cosets(&values, arity);
+            let tree = MerkleTree::<K, F>::new(&leaves);
+            transcript.append_message(b"fri_layer", &tree.root());
+            let beta = transcript.challenge_scalar(b"fri_fold");
+
+            values = fold(&values, generator, beta, arity);
+            generator = generator.pow([arity as u64]);
+            degree_bound = degree_bound.div_ceil(arity);
+            layers.push((tree, leaves));
+        }
+
+        // the layer is low degree for an honest prover, anything above the bound is dropped
+        let mut final_coefficients = interpolate(&values, generator);
+        final_coefficients.truncate(degree_bound);
+        transcript.append_field_elements(b"fri_final", &final_coefficients);
+
+        let queries = self.query_indices(transcript);
+
+        let mut size = self.domain.size;
+        let mut indices = queries;
+        let mut openings = Vec::with_capacity(layers.len());
+        for (tree, leaves) in layers.iter() {
+            let leaf_count = size / arity;
+            let leaf_indices = sorted_unique(indices.iter().map(|index| index % leaf_count).collect());
+
+            openings.push(LayerOpening {
+                leaves: leaf_indices.iter().map(|leaf| (*leaf, leaves[*leaf].clone())).collect(),
+                proof: tree.open_many(&leaf_indices)
+            });
+            indices = leaf_indices;
+            size = leaf_count;
+        }
+
+        FriProof { roots: layers.iter().map(|(tree, _)| tree.root()).collect(), final_coefficients, openings }
+    }
+
+    pub fn verify<K: HashTrait + Default>(&self, proof: &FriProof<F>, transcript: &mut Transcript<K, F>) -> bool {
+        let arity = self.config.arity;
+        let num_layers = self.num_layers();
+        if proof.roots.len() != num_layers || proof.openings.len() != num_layers {
+            return false;
+        }
+
+        let mut betas = Vec::with_capacity(num_layers);
+        for root in proof.roots.iter() {
+            transcript.append_message(b"fri_layer", root);
+            betas.push(transcript.challenge_scalar(b"fri_fold"));
+        }
+
+        let final_degree_bound = (0..num_layers).fold(self.degree_bound, |bound, _| bound.div_ceil(arity));
+        if proof.final_coefficients.len() > final_degree_bound {
+            return false;
+        }
+        transcript.append_field_elements(b"fri_final", &proof.final_coefficients);
+
+        let queries = self.query_indices(transcript);
+
+        // the cosets must be the ones the queries ask for, and be in the committed layer
+        let mut size = self.domain.size;
+        let mut indices = queries.clone();
+        for (root, opening) in proof.roots.iter().zip(proof.openings.iter()) {
+            let leaf_count = size / arity;
+            let leaf_indices = sorted_unique(indices.iter().map(|index| index % leaf_count).collect());
+
+            let opened: Vec<usize> = opening.leaves.iter().map(|(leaf, _)| *leaf).collect();
+            if opened != leaf_indices || opening.leaves.iter().any(|(_, coset)| coset.len() != arity) {
+                return false;
+            }
+            if !MerkleTree::<K, F>::verify_many(root, leaf_count, &opening.leaves, &opening.proof) {
+                return false;
+            }
+
+            indices = leaf_indices;
+            size = leaf_count;
+        }
+
+        let final_poly = UnivariateDense::new(proof.final_coefficients.clone());
+
+        queries.iter().all(|query| {
+            let mut index = *query;
+            let mut size = self.domain.size;
+            let mut generator = self.domain.generator;
+            let mut expected = None;
+
+            for (opening, beta) in proof.openings.iter().zip(betas.iter()) {
+                let leaf_count = size / arity;
+                let (leaf, position) = (index % leaf_count, index / leaf_count);
+                let coset = &opening.leaves.iter().find(|(opened, _)| *opened == leaf).unwrap().1;
+
+                if expected.is_some_and(|value| coset[position] != value) {
+                    return false;
+                }
+
+                let zeta = generator.pow([leaf_count as u64]);
+                expected = Some(fold_coset(coset, generator.pow([leaf as u64]), zeta, *beta));
+
+                index = leaf;
+                size = leaf_count;
+                generator = generator.pow([arity as u64]);
+            }
+
+            expected == Some(final_poly.evaluate(generator.pow([index as u64])))
+        })
+    }
+
+    fn query_indices<K: HashTrait>(&self, transcript: &mut Transcript<K, F>) -> Vec<usize> {
+        transcript.challenge_scalars(b"fri_query", self.config.num_queries).iter()
+            .map(|challenge| challenge.into_bigint().as_ref()[0] as usize % self.domain.size)
+            .collect()
+    }
+}
+
+// leaf i holds the values at i, i + m, ..., i + (a - 1) m for m = n / a, the points whose a-th powers agree
+fn cosets<F: PrimeField>(values: &[F], arity: usize) -> Vec<Vec<F>> {
+    let leaf_count = values.len() / arity;
+
+    (0..leaf_count).map(|i| (0..arity).map(|k| values[i + k * leaf_count]).collect()).collect()
+}
+
+fn fold<F: PrimeField>(values: &[F], generator: F, beta: F, arity: usize) -> Vec<F> {
+    let zeta = generator.pow([(values.len() / arity) as u64]);
+
+    cosets(values, arity).iter().enumerate()
+        .map(|(i, coset)| fold_coset(coset, generator.pow([i as u64]), zeta, beta))
+        .collect()
+}
+
+// f'(x^a) from the values f(x * zeta^k), zeta a primitive a-th root of unity:
+// f_j(x^a) = 1/a * sum_k f(x zeta^k) (x zeta^k)^{-j}, so f'(x^a) = 1/a * sum_k f(x zeta^k) sum_j (beta / (x zeta^k))^j
+fn fold_coset<F: PrimeField>(coset: &[F], x: F, zeta: F, beta: F) -> F {
+    let arity_inverse = F::from(coset.len() as u64).inverse().unwrap();
+    let mut point = x;
+    let mut result = F::zero();
+
+    for value in coset {
+        let ratio = beta * point.inverse().unwrap();
+        let mut power = F::one();
+        let mut sum = F::zero();
+        for _ in 0..coset.len() {
+            sum += power;
+            power *= ratio;
+        }
+
+        result += *value * sum;
+        point *= zeta;
+    }
+
+    result * arity_inverse
+}
+
+// coefficients of the polynomial through the values at the powers of the generator
+fn interpolate<F: PrimeField>(values: &[F], generator: F) -> Vec<F> {
+    let points: Vec<(F, F)> = values.iter().enumerate().map(|(i, value)| (generator.pow([i as u64]), *value)).collect();
+
+    UnivariateDense::interpolate(&points).coefficients
+}
+
+fn sorted_unique(mut indices: Vec<usize>) -> Vec<usize> {
+    indices.sort();
+    indices.dedup();
+
+    indices
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_bn254::Fr;
+    use ark_ff::UniformRand;
+    use rand::{rngs::StdRng, SeedableRng};
+    use sha3::{Digest, Keccak256};
+
+    fn random_poly(degree: usize, rng: &mut StdRng) -> UnivariateDense<Fr> {
+        UnivariateDense::new((0..=degree).map(|_| Fr::rand(rng)).collect())
+    }
+
+    fn prove_and_verify(fri: &Fri<Fr>, evaluations: &[Fr]) -> bool {
+        let proof = fri.prove(evaluations, &mut Transcript::new(b"fri", Keccak256::new()));
+        fri.verify(&proof, &mut Transcript::new(b"fri", Keccak256::new()))
+    }
+
+    #[test]
+    fn test_accepts_low_degree() {
+        let mut rng = StdRng::seed_from_u64(0);
+
+        for (degree_bound, blowup, arity) in [(16, 2, 2), (16, 4, 2), (64, 4, 4), (64, 8, 8), (32, 4, 8)] {
+            let fri = Fri::<Fr>::new(degree_bound, FriConfig { blowup, num_queries: 16, arity }).unwrap();
+            let poly = random_poly(degree_bound - 1, &mut rng);
+
+            assert!(prove_and_verify(&fri, &fri.domain.fft(&poly.coefficients)), "degree bound {degree_bound}, blowup {blowup}, arity {arity}");
+        }
+
+        // lower degrees pass a larger bound
+        let fri = Fri::<Fr>::new(32, FriConfig::default()).unwrap();
+        let poly = random_poly(5, &mut rng);
+        let proof = fri.prove_polynomial(&poly, &mut Transcript::new(b"fri", Keccak256::new()));
+        assert!(fri.verify(&proof, &mut Transcript::new(b"fri", Keccak256::new())));
+
+        assert!(Fri::<Fr>::new(16, FriConfig { blowup: 3, ..FriConfig::default() }).is_none());
+        assert!(Fri::<Fr>::new(16, FriConfig { arity: 1, ..FriConfig::default() }).is_none());
+    }
+
+    #[test]
+    fn test_rejects_high_degree() {
+        let mut rng = StdRng::seed_from_u64(1);
+
+        for arity in [2, 4] {
+            let fri = Fri::<Fr>::new(16, FriConfig { blowup: 4, num_queries: 32, arity }).unwrap();
+
+            // twice the allowed degree, and evaluations of no low degree polynomial at all
+            let poly = random_poly(31, &mut rng);
+            assert!(!prove_and_verify(&fri, &fri.domain.fft(&poly.coefficients)));
+
+            let noise: Vec<Fr> = (0..fri.domain.size).map(|_| Fr::rand(&mut rng)).collect();
+            assert!(!prove_and_verify(&fri, &noise));
+        }
+    }
+
+    #[test]
+    fn test_rejects_tampered_proof() {
+        let mut rng = StdRng::seed_from_u64(2);
+        let fri = Fri::<Fr>::new(16, FriConfig::default()).unwrap();
+        let poly = random_poly(15, &mut rng);
+        let proof = fri.prove_polynomial(&poly, &mut Transcript::new(b"fri", Keccak256::new()));
+
+        let mut tampered = proof.clone();
+        tampered.final_coefficients[0] += Fr::from(1);
+        assert!(!fri.verify(&tampered, &mut Transcript::new(b"fri", Keccak256::new())));
+
+        let mut tampered = proof.clone();
+        tampered.openings[1].leaves[0].1[0] += Fr::from(1);
+        assert!(!fri.verify(&tampered, &mut Transcript::new(b"fri", Keccak256::new())));
+
+        let mut tampered = proof;
+        tampered.roots.pop();
+        assert!(!fri.verify(&tampered, &mut Transcript::new(b"fri", Keccak256::new())));
+    }
+}
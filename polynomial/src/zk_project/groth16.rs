@@ -0,0 +1,250 @@
+use ark_ec::{pairing::Pairing, CurveGroup, PrimeGroup, VariableBaseMSM};
+use ark_ff::{Field, UniformRand};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_std::rand::Rng;
+
+use super::qap::QAP;
+
+// Groth16 over any pairing friendly curve, tested on BLS12-381.
+// Variables follow the R1CS layout z = (1, public inputs, witness).
+
+#[derive(Debug, Clone, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct ProvingKey<E: Pairing> {
+    pub alpha_g1: E::G1Affine,
+    pub beta_g1: E::G1Affine,
+    pub beta_g2: E::G2Affine,
+    pub delta_g1: E::G1Affine,
+    pub delta_g2: E::G2Affine,
+    // u_j(tau) for every variable
+    pub a_query: Vec<E::G1Affine>,
+    // v_j(tau) for every variable, in both groups
+    pub b_g1_query: Vec<E::G1Affine>,
+    pub b_g2_query: Vec<E::G2Affine>,
+    // tau^i * t(tau) / delta for i < n - 1
+    pub h_query: Vec<E::G1Affine>,
+    // (beta * u_j(tau) + alpha * v_j(tau) + w_j(tau)) / delta for the witness variables
+    pub l_query: Vec<E::G1Affine>
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct VerifyingKey<E: Pairing> {
+    pub alpha_g1: E::G1Affine,
+    pub beta_g2: E::G2Affine,
+    pub gamma_g2: E::G2Affine,
+    pub delta_g2: E::G2Affine,
+    // (beta * u_j(tau) + alpha * v_j(tau) + w_j(tau)) / gamma for the constant and the public inputs
+    pub gamma_abc_g1: Vec<E::G1Affine>
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct Proof<E: Pairing> {
+    pub a: E::G1Affine,
+    pub b: E::G2Affine,
+    pub c: E::G1Affine
+}
+
+// Circuit specific setup. The toxic waste is drawn from `rng` and dropped when this returns.
+pub fn setup<E: Pairing, R: Rng>(qap: &QAP<E::ScalarField>, rng: &mut R) -> (ProvingKey<E>, VerifyingKey<E>) {
+    let tau = E::ScalarField::rand(rng);
+    let alpha = E::ScalarField::rand(rng);
+    let beta = E::ScalarField::rand(rng);
+    let gamma = E::ScalarField::rand(rng);
+    let delta = E::ScalarField::rand(rng);
+
+    let gamma_inverse = gamma.inverse().unwrap();
+    let delta_inverse = delta.inverse().unwrap();
+
+    let g1 = E::G1::generator();
+    let g2 = E::G2::generator();
+
+    let u_tau: Vec<E::ScalarField> = qap.u.iter().map(|poly| poly.evaluate(tau)).collect();
+    let v_tau: Vec<E::ScalarField> = qap.v.iter().map(|poly| poly.evaluate(tau)).collect();
+    let w_tau: Vec<E::ScalarField> = qap.w.iter().map(|poly| poly.evaluate(tau)).collect();
+
+    let combined: Vec<E::ScalarField> = (0..qap.num_variables())
+        .map(|j| beta * u_tau[j] + alpha * v_tau[j] + w_tau[j])
+        .collect();
+
+    let t_tau = qap.domain.evaluate_vanishing_polynomial(tau);
+    let mut tau_power = E::ScalarField::from(1u64);
+    let mut h_scalars = Vec::with_capacity(qap.domain.size - 1);
+    for _ in 0..qap.domain.size - 1 {
+        h_scalars.push(tau_power * t_tau * delta_inverse);
+        tau_power *= tau;
+    }
+
+    let scale_g1 = |scalars: &[E::ScalarField]| E::G1::normalize_batch(&scalars.iter().map(|s| g1 * s).collect::<Vec<_>>());
+    let scale_g2 = |scalars: &[E::ScalarField]| E::G2::normalize_batch(&scalars.iter().map(|s| g2 * s).collect::<Vec<_>>());
+
+    let public_variables = 1 + qap.num_public;
+    let gamma_abc: Vec<E::ScalarField> = combined[..public_variables].iter().map(|value| *value * gamma_inverse).collect();
+    let l: Vec<E::ScalarField> = combined[public_variables..].iter().map(|value| *value * delta_inverse).collect();
+
+    let proving_key = ProvingKey {
+        alpha_g1: (g1 * alpha).into_affine(),
+        beta_g1: (g1 * beta).into_affine(),
+        beta_g2: (g2 * beta).into_affine(),
+        delta_g1: (g1 * delta).into_affine(),
+        delta_g2: (g2 * delta).into_affine(),
+        a_query: scale_g1(&u_tau),
+        b_g1_query: scale_g1(&v_tau),
+        b_g2_query: scale_g2(&v_tau),
+        h_query: scale_g1(&h_scalars),
+        l_query: scale_g1(&l)
+    };
+
+    let verifying_key = VerifyingKey {
+        alpha_g1: proving_key.alpha_g1,
+        beta_g2: proving_key.beta_g2,
+        gamma_g2: (g2 * gamma).into_affine(),
+        delta_g2: proving_key.delta_g2,
+        gamma_abc_g1: scale_g1(&gamma_abc)
+    };
+
+    (proving_key, verifying_key)
+}
+
+// Returns None when the assignment does not fit or does not satisfy the QAP.
+pub fn prove<E: Pairing, R: Rng>(proving_key: &ProvingKey<E>, qap: &QAP<E::ScalarField>, public_inputs: &[E::ScalarField], witness: &[E::ScalarField], rng: &mut R) -> Option<Proof<E>> {
+    if public_inputs.len() != qap.num_public || 1 + public_inputs.len() + witness.len() != qap.num_variables() {
+        return None;
+    }
+
+    let mut z = vec![E::ScalarField::from(1u64)];
+    z.extend_from_slice(public_inputs);
+    z.extend_from_slice(witness);
+
+    let h = qap.h_polynomial(&z)?;
+
+    let r = E::ScalarField::rand(rng);
+    let s = E::ScalarField::rand(rng);
+
+    // None when the proving key was generated for a smaller QAP
+    let msm_g1 = |bases: &[E::G1Affine], scalars: &[E::ScalarField]| E::G1::msm(bases.get(..scalars.len())?, scalars).ok();
+
+    let a = msm_g1(&proving_key.a_query, &z)? + proving_key.alpha_g1 + proving_key.delta_g1 * r;
+    let b_g1 = msm_g1(&proving_key.b_g1_query, &z)? + proving_key.beta_g1 + proving_key.delta_g1 * s;
+    let b_g2 = E::G2::msm(proving_key.b_g2_query.get(..z.len())?, &z).ok()? + proving_key.beta_g2 + proving_key.delta_g2 * s;
+
+    let c = msm_g1(&proving_key.l_query, &z[1 + qap.num_public..])?
+        + msm_g1(&proving_key.h_query, &h.coefficients)?
+        + a * s
+        + b_g1 * r
+        - proving_key.delta_g1 * (r * s);
+
+    Some(Proof {
+        a: a.into_affine(),
+        b: b_g2.into_affine(),
+        c: c.into_affine()
+    })
+}
+
+// e(A, B) = e(alpha, beta) * e(sum x_j gamma_abc_j, gamma) * e(C, delta)
+pub fn verify<E: Pairing>(verifying_key: &VerifyingKey<E>, public_inputs: &[E::ScalarField], proof: &Proof<E>) -> bool {
+    if public_inputs.len() + 1 != verifying_key.gamma_abc_g1.len() {
+        return false;
+    }
+
+    let public_commitment = E::G1::msm(&verifying_key.gamma_abc_g1[1..], public_inputs).unwrap() + verifying_key.gamma_abc_g1[0];
+
+    E::pairing(proof.a, proof.b)
+        == E::pairing(verifying_key.alpha_g1, verifying_key.beta_g2)
+            + E::pairing(public_commitment, verifying_key.gamma_g2)
+            + E::pairing(proof.c, verifying_key.delta_g2)
+}
+
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::zk_project::{
+        circuit::{Circuit, Gate, Layer, Operator},
+        r1cs::R1CS,
+    };
+    use ark_bls12_381::{Bls12_381, Fr};
+    use rand::{rngs::StdRng, SeedableRng};
+
+    // x^3 + x + 5 = out, with witness (x, x^2, x^3)
+    fn create_cubic_r1cs() -> R1CS<Fr> {
+        let mut r1cs = R1CS::new(1, 3);
+        let (out, x, x_squared, x_cubed) = (r1cs.public_variable(0), r1cs.witness_variable(0), r1cs.witness_variable(1), r1cs.witness_variable(2));
+
+        r1cs.add_constraint(vec![(x, Fr::from(1))], vec![(x, Fr::from(1))], vec![(x_squared, Fr::from(1))]);
+        r1cs.add_constraint(vec![(x_squared, Fr::from(1))], vec![(x, Fr::from(1))], vec![(x_cubed, Fr::from(1))]);
+        r1cs.add_constraint(vec![(x_cubed, Fr::from(1)), (x, Fr::from(1)), (0, Fr::from(5))], vec![(0, Fr::from(1))], vec![(out, Fr::from(1))]);
+
+        r1cs
+    }
+
+    #[test]
+    fn test_groth16_cubic() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let qap = QAP::from_r1cs(&create_cubic_r1cs());
+        let (proving_key, verifying_key) = setup::<Bls12_381, _>(&qap, &mut rng);
+
+        let public_inputs = [Fr::from(35)];
+        let proof = prove(&proving_key, &qap, &public_inputs, &[Fr::from(3), Fr::from(9), Fr::from(27)], &mut rng).unwrap();
+
+        assert!(verify(&verifying_key, &public_inputs, &proof));
+        assert!(!verify(&verifying_key, &[Fr::from(36)], &proof));
+    }
+
+    #[test]
+    fn test_groth16_rejects_tampered_witness_and_proof() {
+        let mut rng = StdRng::seed_from_u64(1);
+        let qap = QAP::from_r1cs(&create_cubic_r1cs());
+        let (proving_key, verifying_key) = setup::<Bls12_381, _>(&qap, &mut rng);
+
+        let public_inputs = [Fr::from(35)];
+        assert!(prove(&proving_key, &qap, &public_inputs, &[Fr::from(3), Fr::from(9), Fr::from(28)], &mut rng).is_none());
+
+        // assignments of the wrong shape are rejected rather than panicking
+        assert!(prove(&proving_key, &qap, &[], &[Fr::from(3), Fr::from(9), Fr::from(27)], &mut rng).is_none());
+        assert!(prove(&proving_key, &qap, &public_inputs, &[Fr::from(3), Fr::from(9)], &mut rng).is_none());
+        assert!(prove(&proving_key, &qap, &public_inputs, &[Fr::from(3), Fr::from(9), Fr::from(27), Fr::from(0)], &mut rng).is_none());
+
+        let mut proof = prove(&proving_key, &qap, &public_inputs, &[Fr::from(3), Fr::from(9), Fr::from(27)], &mut rng).unwrap();
+        proof.c = (proof.c + proving_key.delta_g1).into_affine();
+
+        assert!(!verify(&verifying_key, &public_inputs, &proof));
+    }
+
+    #[test]
+    fn test_groth16_proof_size() {
+        let mut rng = StdRng::seed_from_u64(2);
+        let qap = QAP::from_r1cs(&create_cubic_r1cs());
+        let (proving_key, verifying_key) = setup::<Bls12_381, _>(&qap, &mut rng);
+
+        let public_inputs = [Fr::from(35)];
+        let proof = prove(&proving_key, &qap, &public_inputs, &[Fr::from(3), Fr::from(9), Fr::from(27)], &mut rng).unwrap();
+
+        // two compressed G1 points and one compressed G2 point
+        assert_eq!(proof.compressed_size(), 48 + 96 + 48);
+
+        let mut bytes = Vec::new();
+        proof.serialize_compressed(&mut bytes).unwrap();
+        let decoded = Proof::<Bls12_381>::deserialize_compressed(bytes.as_slice()).unwrap();
+
+        assert_eq!(decoded, proof);
+        assert!(verify(&verifying_key, &public_inputs, &decoded));
+    }
+
+    #[test]
+    fn test_groth16_from_circuit() {
+        let mut rng = StdRng::seed_from_u64(3);
+        let layer0 = Layer::new(vec![Gate::new(0, 1, 0, Operator::Mul), Gate::new(0, 1, 1, Operator::Add)]);
+        let layer1 = Layer::new(vec![Gate::new(0, 1, 0, Operator::Add), Gate::new(2, 3, 1, Operator::Mul)]);
+
+        let mut circuit = Circuit::<Fr>::new(vec![layer0, layer1]);
+        circuit.evaluate(vec![Fr::from(2), Fr::from(3), Fr::from(4), Fr::from(5)]);
+
+        let r1cs = R1CS::from_circuit(&circuit);
+        let (public_inputs, witness) = R1CS::circuit_assignment(&circuit);
+
+        let qap = QAP::from_r1cs(&r1cs);
+        let (proving_key, verifying_key) = setup::<Bls12_381, _>(&qap, &mut rng);
+        let proof = prove(&proving_key, &qap, &public_inputs, &witness, &mut rng).unwrap();
+
+        assert!(verify(&verifying_key, &public_inputs, &proof));
+    }
+}
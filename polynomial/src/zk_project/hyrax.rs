@@ -0,0 +1,323 @@
+use ark_bn254::{Fr, G1Affine, G1Projective};
+use ark_ec::{CurveGroup, VariableBaseMSM};
+use ark_ff::Field;
+use ark_serialize::CanonicalSerialize;
+
+use crate::verifiable_secret_sharing::hash_to_generator;
+
+use super::{
+    multilinear_commitment::MultilinearCommitment,
+    multilinear_polynomial::{eq_evaluations, MultilinearPoly},
+    transcript::{HashTrait, Transcript},
+};
+
+// Hyrax commitment over BN254 G1, transparent and based on discrete logs.
+//
+// The 2^m evaluations are laid out row by row in a 2^{m - c} x 2^c matrix M, c = ceil(m / 2), as for
+// Ligero, and every row is committed with a Pedersen vector commitment C_i = <M_i, G>.
+// For f(point) = a^T M b the verifier combines the row commitments into C = sum_i a_i C_i, a commitment
+// to the row v = a^T M, and the prover shows <v, b> = value with a Bulletproofs inner product argument,
+// which takes log 2^c rounds of two group elements each.
+// The generators are hashed to the curve, so nobody knows discrete logs between them. Not hiding.
+pub struct Hyrax {
+    pub generators: Vec<G1Affine>,
+    // the generator the inner product is committed with
+    pub u: G1Affine
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HyraxProof {
+    pub l: Vec<G1Affine>,
+    pub r: Vec<G1Affine>,
+    // the row folded down to a single element
+    pub a: Fr
+}
+
+impl Hyrax {
+    // generators for polynomials of up to max_num_vars variables
+    pub fn new(max_num_vars: usize) -> Self {
+        let (_, row_length) = dimensions(max_num_vars);
+        let generators = (0..row_length as u64)
+            .map(|i| hash_to_generator::<G1Projective>(&[b"hyrax-g".as_slice(), &i.to_be_bytes()].concat()))
+            .collect::<Vec<_>>();
+
+        Self { generators: G1Projective::normalize_batch(&generators), u: hash_to_generator::<G1Projective>(b"hyrax-u").into_affine() }
+    }
+
+    fn pedersen(&self, values: &[Fr]) -> G1Projective {
+        G1Projective::msm(&self.generators[..values.len()], values).unwrap()
+    }
+}
+
+impl MultilinearCommitment<Fr> for Hyrax {
+    // one commitment per row of the evaluation matrix
+    type Commitment = Vec<G1Affine>;
+    // the row commitments again, the opening absorbs them
+    type ProverData = Vec<G1Affine>;
+    type Proof = HyraxProof;
+
+    fn commit(&self, poly: &MultilinearPoly<Fr>) -> (Self::Commitment, Self::ProverData) {
+        assert!(poly.coefficients.len().is_power_of_two(), "evaluations must cover a hypercube");
+
+        let (_, row_length) = dimensions(poly.coefficients.len().trailing_zeros() as usize);
+        assert!(row_length <= self.generators.len(), "polynomial has too many variables for these generators");
+
+        let rows: Vec<G1Projective> = poly.coefficients.chunks(row_length).map(|row| self.pedersen(row)).collect();
+
+        let commitment = G1Projective::normalize_batch(&rows);
+
+        (commitment.clone(), commitment)
+    }
+
+    fn commitment_bytes(commitment: &Self::Commitment) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        commitment.serialize_compressed(&mut bytes).expect("serializing into a vector cannot fail");
+
+        bytes
+    }
+
+    fn open<K: HashTrait>(&self, poly: &MultilinearPoly<Fr>, prover_data: &Self::ProverData, point: &[Fr], transcript: &mut Transcript<K, Fr>) -> Self::Proof {
+        assert_eq!(poly.coefficients.len(), 1 << point.len(), "point must have one coordinate per variable");
+
+        let (_, row_length) = dimensions(point.len());
+        assert!(row_length <= self.generators.len(), "polynomial has too many variables for these generators");
+
+        let (row_point, column_point) = point.split_at(point.len() - row_length.trailing_zeros() as usize);
+
+        let row_weights = eq_evaluations(row_point);
+        let mut a: Vec<Fr> = (0..row_length)
+            .map(|j| poly.coefficients.chunks(row_length).zip(row_weights.iter()).map(|(row, weight)| row[j] * weight).sum())
+            .collect();
+        let mut b = eq_evaluations(column_point);
+        let mut g: Vec<G1Projective> = self.generators[..row_length].iter().map(|generator| (*generator).into()).collect();
+
+        absorb_statement(transcript, prover_data, point, inner_product(&a, &b));
+        let u = self.u * transcript.challenge_scalar(b"hyrax_u");
+        let (mut l, mut r) = (Vec::new(), Vec::new());
+
+        while a.len() > 1 {
+            let half = a.len() / 2;
+            let (a_left, a_right) = a.split_at(half);
+            let (b_left, b_right) = b.split_at(half);
+            let (g_left, g_right) = g.split_at(half);
+
+            let l_point = msm(g_right, a_left) + u * inner_product(a_left, b_right);
+            let r_point = msm(g_left, a_right) + u * inner_product(a_right, b_left);
+            transcript.append_point(b"hyrax_l", &l_point.into_affine());
+            transcript.append_point(b"hyrax_r", &r_point.into_affine());
+
+            let x = transcript.challenge_scalar(b"hyrax_round");
+            let x_inverse = x.inverse().unwrap();
+
+            a = fold(a_left, a_right, x, x_inverse);
+            b = fold(b_left, b_right, x_inverse, x);
+            g = g_left.iter().zip(g_right.iter()).map(|(left, right)| *left * x_inverse + *right * x).collect();
+
+            l.push(l_point.into_affine());
+            r.push(r_point.into_affine());
+        }
+
+        HyraxProof { l, r, a: a[0] }
+    }
+
+    fn verify<K: HashTrait>(&self, commitment: &Self::Commitment, point: &[Fr], value: Fr, proof: &Self::Proof, transcript: &mut Transcript<K, Fr>) -> bool {
+        let (num_rows, row_length) = dimensions(point.len());
+        let rounds = row_length.trailing_zeros() as usize;
+        if commitment.len() != num_rows || row_length > self.generators.len() || proof.l.len() != rounds || proof.r.len() != rounds {
+            return false;
+        }
+
+        let (row_point, column_point) = point.split_at(point.len() - rounds);
+
+        // commitment to a^T M, then bound to the claimed inner product
+        absorb_statement(transcript, commitment, point, value);
+        let u = self.u * transcript.challenge_scalar(b"hyrax_u");
+        let mut p = G1Projective::msm(commitment, &eq_evaluations(row_point)).unwrap() + u * value;
+
+        let mut b = eq_evaluations(column_point);
+        let mut g: Vec<G1Projective> = self.generators[..row_length].iter().map(|generator| (*generator).into()).collect();
+
+        for (l_point, r_point) in proof.l.iter().zip(proof.r.iter()) {
+            transcript.append_point(b"hyrax_l", l_point);
+            transcript.append_point(b"hyrax_r", r_point);
+
+            let x = transcript.challenge_scalar(b"hyrax_round");
+            let x_inverse = x.inverse().unwrap();
+
+            p += *l_point * x.square() + *r_point * x_inverse.square();
+
+            let half = b.len() / 2;
+            b = fold(&b[..half], &b[half..], x_inverse, x);
+            g = g[..half].iter().zip(g[half..].iter()).map(|(left, right)| *left * x_inverse + *right * x).collect();
+        }
+
+        p == g[0] * proof.a + u * (proof.a * b[0])
+    }
+}
+
+// Every challenge depends on the commitment, the point and the claimed value, so none of them can be
+// chosen after the challenges are known.
+fn absorb_statement<K: HashTrait>(transcript: &mut Transcript<K, Fr>, commitment: &[G1Affine], point: &[Fr], value: Fr) {
+    for row in commitment.iter() {
+        transcript.append_point(b"hyrax_commitment", row);
+    }
+    transcript.append_field_elements(b"hyrax_point", point);
+    transcript.append_field_element(b"hyrax_value", &value);
+}
+
+// rows x columns of the evaluation matrix, the same layout as Ligero's
+fn dimensions(num_vars: usize) -> (usize, usize) {
+    let column_vars = num_vars.div_ceil(2);
+
+    (1 << (num_vars - column_vars), 1 << column_vars)
+}
+
+fn msm(bases: &[G1Projective], scalars: &[Fr]) -> G1Projective {
+    bases.iter().zip(scalars.iter()).map(|(base, scalar)| *base * scalar).sum()
+}
+
+fn inner_product(a: &[Fr], b: &[Fr]) -> Fr {
+    a.iter().zip(b.iter()).map(|(x, y)| *x * y).sum()
+}
+
+// left * x + right * y, element-wise
+fn fold(left: &[Fr], right: &[Fr], x: Fr, y: Fr) -> Vec<Fr> {
+    left.iter().zip(right.iter()).map(|(l, r)| *l * x + *r * y).collect()
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::zk_project::{
+        circuit::{Circuit, Gate, Layer, Operator},
+        data_parallel_gkr::DataParallelCircuit,
+    };
+    use ark_ff::UniformRand;
+    use rand::{rngs::StdRng, SeedableRng};
+    use sha3::{Digest, Keccak256};
+
+    fn transcript() -> Transcript<Keccak256, Fr> {
+        Transcript::new(b"hyrax", Keccak256::new())
+    }
+
+    #[test]
+    fn test_hyrax_opening() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let hyrax = Hyrax::new(6);
+
+        for num_vars in [1usize, 2, 5, 6] {
+            let poly = MultilinearPoly::new((0..1 << num_vars).map(|_| Fr::rand(&mut rng)).collect());
+            let point: Vec<Fr> = (0..num_vars).map(|_| Fr::rand(&mut rng)).collect();
+            let value = poly.evaluate(&point);
+
+            let (commitment, prover_data) = hyrax.commit(&poly);
+            let proof = hyrax.open(&poly, &prover_data, &point, &mut transcript());
+
+            assert_eq!(proof.l.len(), num_vars.div_ceil(2));
+            assert!(hyrax.verify(&commitment, &point, value, &proof, &mut transcript()));
+            assert!(!hyrax.verify(&commitment, &point, value + Fr::from(1), &proof, &mut transcript()));
+        }
+    }
+
+    #[test]
+    fn test_hyrax_rejects_other_polynomial() {
+        let mut rng = StdRng::seed_from_u64(1);
+        let hyrax = Hyrax::new(4);
+        let poly = MultilinearPoly::new((0..16).map(|_| Fr::rand(&mut rng)).collect());
+        let other = MultilinearPoly::new((0..16).map(|_| Fr::rand(&mut rng)).collect());
+        let point: Vec<Fr> = (0..4).map(|_| Fr::rand(&mut rng)).collect();
+
+        let (commitment, prover_data) = hyrax.commit(&poly);
+        let (_, other_data) = hyrax.commit(&other);
+        let proof = hyrax.open(&other, &other_data, &point, &mut transcript());
+        assert!(!hyrax.verify(&commitment, &point, other.evaluate(&point), &proof, &mut transcript()));
+
+        let mut proof = hyrax.open(&poly, &prover_data, &point, &mut transcript());
+        proof.a += Fr::from(1);
+        assert!(!hyrax.verify(&commitment, &point, poly.evaluate(&point), &proof, &mut transcript()));
+    }
+
+    #[test]
+    #[should_panic(expected = "too many variables")]
+    fn test_hyrax_open_rejects_too_many_variables() {
+        let mut rng = StdRng::seed_from_u64(4);
+        let hyrax = Hyrax::new(2);
+        let poly = MultilinearPoly::new((0..64).map(|_| Fr::rand(&mut rng)).collect());
+        let point: Vec<Fr> = (0..6).map(|_| Fr::rand(&mut rng)).collect();
+
+        hyrax.open(&poly, &Vec::new(), &point, &mut transcript());
+    }
+
+    #[test]
+    #[should_panic(expected = "one coordinate per variable")]
+    fn test_hyrax_open_rejects_short_point() {
+        let mut rng = StdRng::seed_from_u64(5);
+        let hyrax = Hyrax::new(4);
+        let poly = MultilinearPoly::new((0..16).map(|_| Fr::rand(&mut rng)).collect());
+        let (_, prover_data) = hyrax.commit(&poly);
+        let point: Vec<Fr> = (0..3).map(|_| Fr::rand(&mut rng)).collect();
+
+        hyrax.open(&poly, &prover_data, &point, &mut transcript());
+    }
+
+    #[test]
+    fn test_hyrax_opening_is_bound_to_statement() {
+        let mut rng = StdRng::seed_from_u64(2);
+        let hyrax = Hyrax::new(4);
+        let poly = MultilinearPoly::new((0..16).map(|_| Fr::rand(&mut rng)).collect());
+        let point: Vec<Fr> = (0..4).map(|_| Fr::rand(&mut rng)).collect();
+        let value = poly.evaluate(&point);
+
+        let (commitment, prover_data) = hyrax.commit(&poly);
+        let proof = hyrax.open(&poly, &prover_data, &point, &mut transcript());
+        assert!(hyrax.verify(&commitment, &point, value, &proof, &mut transcript()));
+
+        // the same commitment opened to a wrong value, each check on a fresh transcript
+        // that only the scheme itself writes to
+        for wrong_value in [value + Fr::from(1), Fr::from(0), Fr::rand(&mut rng)] {
+            assert!(!hyrax.verify(&commitment, &point, wrong_value, &proof, &mut transcript()));
+        }
+
+        // the proof is tied to the point it was made for
+        let mut other_point = point.clone();
+        other_point[0] += Fr::from(1);
+        assert!(!hyrax.verify(&commitment, &other_point, poly.evaluate(&other_point), &proof, &mut transcript()));
+    }
+
+    // GKR reduces the circuit to claims on its input layer, which are opened against a Hyrax
+    // commitment to the inputs instead of being checked on the inputs themselves
+    #[test]
+    fn test_hyrax_with_gkr_input_claims() {
+        let layer0 = Layer::new(vec![Gate::new(0, 1, 0, Operator::Mul)]);
+        let layer1 = Layer::new(vec![Gate::new(0, 1, 0, Operator::Add), Gate::new(2, 3, 1, Operator::Mul)]);
+        let mut circuit = DataParallelCircuit::new(Circuit::<Fr>::new(vec![layer0, layer1]), 4);
+
+        let inputs: Vec<Vec<Fr>> = (0..4u64).map(|copy| (1..=4).map(|i| Fr::from(copy * 4 + i)).collect()).collect();
+        circuit.evaluate(&inputs);
+        let input_poly = MultilinearPoly::new(inputs.concat());
+
+        let hyrax = Hyrax::new(4);
+        let (commitment, prover_data) = hyrax.commit(&input_poly);
+
+        let proof = circuit.prove(Keccak256::new());
+
+        // the prover finds the same points by replaying the verifier
+        let input_claims = circuit.reduce_to_input_claims(4, &proof, Keccak256::new()).unwrap();
+        let openings: Vec<(Fr, HyraxProof)> = input_claims.points.iter().map(|(_, point)| {
+            let mut transcript = transcript();
+            transcript.append_message(b"input_commitment", &Hyrax::commitment_bytes(&commitment));
+            (input_poly.evaluate(point), hyrax.open(&input_poly, &prover_data, point, &mut transcript))
+        }).collect();
+
+        let verifier_claims = circuit.reduce_to_input_claims(4, &proof, Keccak256::new()).unwrap();
+        let opened_claim: Fr = verifier_claims.points.iter().zip(openings.iter()).map(|((weight, point), (value, opening))| {
+            let mut transcript = transcript();
+            transcript.append_message(b"input_commitment", &Hyrax::commitment_bytes(&commitment));
+            assert!(hyrax.verify(&commitment, point, *value, opening, &mut transcript));
+            *weight * value
+        }).sum();
+
+        assert_eq!(opened_claim, verifier_claims.claim);
+    }
+}
@@ -0,0 +1,240 @@
+use std::marker::PhantomData;
+
+use ark_ff::PrimeField;
+
+use crate::reed_solomon::ReedSolomon;
+
+use super::{
+    merkle_tree::{MerkleTree, MultiProof},
+    multilinear_commitment::MultilinearCommitment,
+    multilinear_polynomial::{eq_evaluations, MultilinearPoly},
+    partial_sumcheck::to_bytes,
+    transcript::{HashTrait, Transcript},
+};
+
+// Ligero-style commitment from a Reed-Solomon code, transparent and without pairings.
+//
+// The 2^m evaluations are laid out row by row in a 2^{m - c} x 2^c matrix M, c = ceil(m / 2), so the
+// first variables of a point select the row and the last ones the column, and
+//     f(point) = a^T M b with a = eq(point_rows, .), b = eq(point_columns, .)
+// Every row is encoded and the columns of the encoded matrix are the leaves of a Merkle tree.
+//
+// An opening sends the combinations r^T M for a random r (proximity) and a^T M (evaluation).
+// Since encoding is linear, their encodings must agree with the same combinations of any encoded
+// column, which the verifier spot checks on randomly chosen opened columns. The value is then <a^T M, b>.
+// The hash H of the Merkle tree is independent of the transcript's.
+pub struct Ligero<H: HashTrait + Default> {
+    // inverse rate of the code
+    pub blowup: usize,
+    pub num_column_openings: usize,
+    _marker: PhantomData<H>
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LigeroCommitment {
+    pub root: Vec<u8>,
+    pub num_vars: usize
+}
+
+pub struct LigeroProverData<F: PrimeField, H: HashTrait + Default> {
+    encoded_rows: Vec<Vec<F>>,
+    tree: MerkleTree<H, F>
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LigeroProof<F: PrimeField> {
+    pub random_combination: Vec<F>,
+    pub evaluation_combination: Vec<F>,
+    // the opened columns of the encoded matrix, as (column index, column)
+    pub columns: Vec<(usize, Vec<F>)>,
+    pub column_proof: MultiProof
+}
+
+impl <H: HashTrait + Default> Ligero<H> {
+    // None unless the blowup is a power of two of at least 2
+    pub fn new(blowup: usize, num_column_openings: usize) -> Option<Self> {
+        if blowup < 2 || !blowup.is_power_of_two() {
+            return None;
+        }
+
+        Some(Self { blowup, num_column_openings, _marker: PhantomData })
+    }
+
+    // rows x columns of the evaluation matrix
+    fn dimensions(num_vars: usize) -> (usize, usize) {
+        let column_vars = num_vars.div_ceil(2);
+
+        (1 << (num_vars - column_vars), 1 << column_vars)
+    }
+
+    // over the NTT domain when the field has one, over 1, ..., n otherwise
+    fn code<F: PrimeField>(&self, row_length: usize) -> ReedSolomon<F> {
+        ReedSolomon::new(row_length, self.blowup).unwrap_or_else(|| {
+            let points = (1..=(row_length * self.blowup) as u64).map(F::from).collect();
+            ReedSolomon::with_points(row_length, points).unwrap()
+        })
+    }
+
+    fn column_indices<K: HashTrait, F: PrimeField>(&self, codeword_length: usize, transcript: &mut Transcript<K, F>) -> Vec<usize> {
+        let mut indices: Vec<usize> = transcript.challenge_scalars(b"ligero_columns", self.num_column_openings).iter()
+            .map(|challenge| (challenge.into_bigint().as_ref()[0] % codeword_length as u64) as usize)
+            .collect();
+        indices.sort();
+        indices.dedup();
+
+        indices
+    }
+}
+
+impl <F: PrimeField, H: HashTrait + Default> MultilinearCommitment<F> for Ligero<H> {
+    type Commitment = LigeroCommitment;
+    type ProverData = LigeroProverData<F, H>;
+    type Proof = LigeroProof<F>;
+
+    fn commit(&self, poly: &MultilinearPoly<F>) -> (Self::Commitment, Self::ProverData) {
+        assert!(poly.coefficients.len().is_power_of_two(), "evaluations must cover a hypercube");
+
+        let num_vars = poly.coefficients.len().trailing_zeros() as usize;
+        let (_, row_length) = Self::dimensions(num_vars);
+        let code = self.code::<F>(row_length);
+
+        let encoded_rows: Vec<Vec<F>> = poly.coefficients.chunks(row_length).map(|row| code.encode(row)).collect();
+        let columns: Vec<Vec<F>> = (0..code.codeword_length()).map(|j| encoded_rows.iter().map(|row| row[j]).collect()).collect();
+        let tree = MerkleTree::new(&columns);
+
+        (LigeroCommitment { root: tree.root(), num_vars }, LigeroProverData { encoded_rows, tree })
+    }
+
+    fn commitment_bytes(commitment: &Self::Commitment) -> Vec<u8> {
+        let mut bytes = commitment.root.clone();
+        bytes.extend((commitment.num_vars as u64).to_be_bytes());
+
+        bytes
+    }
+
+    fn open<K: HashTrait>(&self, poly: &MultilinearPoly<F>, prover_data: &Self::ProverData, point: &[F], transcript: &mut Transcript<K, F>) -> Self::Proof {
+        let (num_rows, row_length) = Self::dimensions(point.len());
+        let rows: Vec<&[F]> = poly.coefficients.chunks(row_length).collect();
+
+        let combine = |weights: &[F]| -> Vec<F> {
+            (0..row_length).map(|j| rows.iter().zip(weights.iter()).map(|(row, weight)| row[j] * weight).sum()).collect()
+        };
+
+        absorb_statement(transcript, &prover_data.tree.root(), point, poly.evaluate(&point.to_vec()));
+        let random_weights = transcript.challenge_scalars(b"ligero_proximity", num_rows);
+        let random_combination = combine(&random_weights);
+        transcript.append_message(b"ligero_proximity_row", &to_bytes(&random_combination));
+
+        let evaluation_combination = combine(&eq_evaluations(&point[..point.len() - row_length.trailing_zeros() as usize]));
+        transcript.append_message(b"ligero_evaluation_row", &to_bytes(&evaluation_combination));
+
+        let indices = self.column_indices(prover_data.tree.num_leaves(), transcript);
+        let columns = indices.iter().map(|j| (*j, prover_data.encoded_rows.iter().map(|row| row[*j]).collect())).collect();
+
+        LigeroProof { random_combination, evaluation_combination, columns, column_proof: prover_data.tree.open_many(&indices) }
+    }
+
+    fn verify<K: HashTrait>(&self, commitment: &Self::Commitment, point: &[F], value: F, proof: &Self::Proof, transcript: &mut Transcript<K, F>) -> bool {
+        if point.len() != commitment.num_vars {
+            return false;
+        }
+
+        let (num_rows, row_length) = Self::dimensions(point.len());
+        if proof.random_combination.len() != row_length || proof.evaluation_combination.len() != row_length {
+            return false;
+        }
+
+        let code = self.code::<F>(row_length);
+        let (row_point, column_point) = point.split_at(point.len() - row_length.trailing_zeros() as usize);
+
+        absorb_statement(transcript, &commitment.root, point, value);
+        let random_weights = transcript.challenge_scalars(b"ligero_proximity", num_rows);
+        transcript.append_message(b"ligero_proximity_row", &to_bytes(&proof.random_combination));
+        transcript.append_message(b"ligero_evaluation_row", &to_bytes(&proof.evaluation_combination));
+
+        let indices = self.column_indices(code.codeword_length(), transcript);
+        let opened: Vec<usize> = proof.columns.iter().map(|(j, _)| *j).collect();
+        if opened != indices || proof.columns.iter().any(|(_, column)| column.len() != num_rows) {
+            return false;
+        }
+        if !MerkleTree::<H, F>::verify_many(&commitment.root, code.codeword_length(), &proof.columns, &proof.column_proof) {
+            return false;
+        }
+
+        let row_weights = eq_evaluations(row_point);
+        let encoded_random = code.encode(&proof.random_combination);
+        let encoded_evaluation = code.encode(&proof.evaluation_combination);
+
+        let consistent = proof.columns.iter().all(|(j, column)| {
+            let dot = |weights: &[F]| -> F { column.iter().zip(weights.iter()).map(|(x, w)| *x * w).sum() };
+            dot(&random_weights) == encoded_random[*j] && dot(&row_weights) == encoded_evaluation[*j]
+        });
+
+        let claimed: F = proof.evaluation_combination.iter().zip(eq_evaluations(column_point)).map(|(x, w)| *x * w).sum();
+
+        consistent && claimed == value
+    }
+}
+
+// The challenges must depend on the statement, or the spot checks would not be bound to it.
+fn absorb_statement<K: HashTrait, F: PrimeField>(transcript: &mut Transcript<K, F>, root: &[u8], point: &[F], value: F) {
+    transcript.append_message(b"ligero_root", root);
+    transcript.append_field_elements(b"ligero_point", point);
+    transcript.append_field_element(b"ligero_value", &value);
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_bn254::Fr;
+    use ark_ff::UniformRand;
+    use rand::{rngs::StdRng, SeedableRng};
+    use sha3::{Digest, Keccak256};
+
+    fn transcript() -> Transcript<Keccak256, Fr> {
+        Transcript::new(b"ligero", Keccak256::new())
+    }
+
+    #[test]
+    fn test_ligero_opening() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let ligero = Ligero::<Keccak256>::new(4, 20).unwrap();
+        assert!(Ligero::<Keccak256>::new(3, 20).is_none());
+
+        for num_vars in [1, 4, 5] {
+            let poly = MultilinearPoly::new((0..1 << num_vars).map(|_| Fr::rand(&mut rng)).collect());
+            let point: Vec<Fr> = (0..num_vars).map(|_| Fr::rand(&mut rng)).collect();
+            let value = poly.evaluate(&point);
+
+            let (commitment, prover_data) = ligero.commit(&poly);
+            let proof = ligero.open(&poly, &prover_data, &point, &mut transcript());
+
+            assert!(ligero.verify(&commitment, &point, value, &proof, &mut transcript()));
+            assert!(!ligero.verify(&commitment, &point, value + Fr::from(1), &proof, &mut transcript()));
+        }
+    }
+
+    #[test]
+    fn test_ligero_rejects_inconsistent_rows() {
+        let mut rng = StdRng::seed_from_u64(1);
+        let ligero = Ligero::<Keccak256>::new(4, 20).unwrap();
+        let poly = MultilinearPoly::new((0..16).map(|_| Fr::rand(&mut rng)).collect());
+        let point: Vec<Fr> = (0..4).map(|_| Fr::rand(&mut rng)).collect();
+
+        let (commitment, prover_data) = ligero.commit(&poly);
+        let proof = ligero.open(&poly, &prover_data, &point, &mut transcript());
+
+        // a different evaluation row changes the claimed value but no longer matches the columns
+        let mut tampered = proof.clone();
+        tampered.evaluation_combination[0] += Fr::from(1);
+        let value: Fr = tampered.evaluation_combination.iter().zip(eq_evaluations(&point[2..])).map(|(x, w)| *x * w).sum();
+        assert!(!ligero.verify(&commitment, &point, value, &tampered, &mut transcript()));
+
+        // opening another polynomial against the commitment
+        let other = MultilinearPoly::new((0..16).map(|_| Fr::rand(&mut rng)).collect());
+        let (_, other_data) = ligero.commit(&other);
+        let proof = ligero.open(&other, &other_data, &point, &mut transcript());
+        assert!(!ligero.verify(&commitment, &point, other.evaluate(&point), &proof, &mut transcript()));
+    }
+}
@@ -0,0 +1,242 @@
+use std::marker::PhantomData;
+
+use ark_ff::PrimeField;
+
+use super::{partial_sumcheck::to_bytes, transcript::HashTrait};
+
+const LEAF_PREFIX: u8 = 0;
+const NODE_PREFIX: u8 = 1;
+
+// Vector commitment to a list of leaves, each a slice of field elements (a single codeword symbol,
+// a FRI coset or a column of a matrix). Leaves and inner nodes are hashed with different prefixes
+// so a leaf can never pass for a node. The leaf count is padded to a power of two with empty leaves.
+pub struct MerkleTree<K: HashTrait + Default, F: PrimeField> {
+    // layers[0] holds the leaf digests, the last layer only the root
+    layers: Vec<Vec<Vec<u8>>>,
+    num_leaves: usize,
+    _marker: PhantomData<(K, F)>
+}
+
+// The siblings on the path from a leaf to the root, from the bottom up.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MerkleProof {
+    pub siblings: Vec<Vec<u8>>
+}
+
+// An opening of several leaves at once. Nodes that lie on the path of more than one opened leaf,
+// or that can be computed from opened leaves, are left out. Siblings are listed level by level
+// from the bottom up, in increasing index order within a level.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MultiProof {
+    pub siblings: Vec<Vec<u8>>
+}
+
+impl <K: HashTrait + Default, F: PrimeField> MerkleTree<K, F> {
+    pub fn new(leaves: &[Vec<F>]) -> Self {
+        assert!(!leaves.is_empty(), "no leaves to commit to");
+
+        let mut layer: Vec<Vec<u8>> = leaves.iter().map(|leaf| hash_leaf::<K, F>(leaf)).collect();
+        layer.resize(leaves.len().next_power_of_two(), hash_leaf::<K, F>(&[]));
+
+        let mut layers = vec![layer];
+        while layers.last().unwrap().len() > 1 {
+            let next = layers.last().unwrap().chunks(2).map(|pair| hash_node::<K>(&pair[0], &pair[1])).collect();
+            layers.push(next);
+        }
+
+        Self { layers, num_leaves: leaves.len(), _marker: PhantomData }
+    }
+
+    // one field element per leaf
+    pub fn from_elements(elements: &[F]) -> Self {
+        Self::new(&elements.iter().map(|element| vec![*element]).collect::<Vec<_>>())
+    }
+
+    pub fn root(&self) -> Vec<u8> {
+        self.layers.last().unwrap()[0].clone()
+    }
+
+    pub fn num_leaves(&self) -> usize {
+        self.num_leaves
+    }
+
+    pub fn open(&self, index: usize) -> MerkleProof {
+        assert!(index < self.num_leaves, "leaf index out of range");
+
+        let siblings = self.layers[..self.layers.len() - 1].iter().enumerate()
+            .map(|(level, layer)| layer[(index >> level) ^ 1].clone())
+            .collect();
+
+        MerkleProof { siblings }
+    }
+
+    pub fn open_many(&self, indices: &[usize]) -> MultiProof {
+        assert!(indices.iter().all(|index| *index < self.num_leaves), "leaf index out of range");
+
+        let mut known = sorted_unique(indices.to_vec());
+        let mut siblings = Vec::new();
+
+        for layer in self.layers[..self.layers.len() - 1].iter() {
+            for index in known.iter() {
+                if !known.contains(&(index ^ 1)) {
+                    siblings.push(layer[index ^ 1].clone());
+                }
+            }
+            known = sorted_unique(known.iter().map(|index| index / 2).collect());
+        }
+
+        MultiProof { siblings }
+    }
+
+    pub fn verify(root: &[u8], num_leaves: usize, index: usize, leaf: &[F], proof: &MerkleProof) -> bool {
+        if index >= num_leaves || proof.siblings.len() != depth(num_leaves) {
+            return false;
+        }
+
+        let computed = proof.siblings.iter().enumerate().fold(hash_leaf::<K, F>(leaf), |node, (level, sibling)| {
+            if (index >> level) & 1 == 0 {
+                hash_node::<K>(&node, sibling)
+            } else {
+                hash_node::<K>(sibling, &node)
+            }
+        });
+
+        computed == root
+    }
+
+    // leaves are given as (index, leaf), in any order
+    pub fn verify_many(root: &[u8], num_leaves: usize, leaves: &[(usize, Vec<F>)], proof: &MultiProof) -> bool {
+        if leaves.is_empty() || leaves.iter().any(|(index, _)| *index >= num_leaves) {
+            return false;
+        }
+
+        let mut known: Vec<(usize, Vec<u8>)> = leaves.iter().map(|(index, leaf)| (*index, hash_leaf::<K, F>(leaf))).collect();
+        known.sort_by_key(|(index, _)| *index);
+        // the same index opened twice must be the same leaf
+        for pair in known.windows(2) {
+            if pair[0].0 == pair[1].0 && pair[0].1 != pair[1].1 {
+                return false;
+            }
+        }
+        known.dedup_by_key(|(index, _)| *index);
+
+        let mut siblings = proof.siblings.iter();
+        for _ in 0..depth(num_leaves) {
+            let mut next = Vec::with_capacity(known.len());
+            let mut i = 0;
+            while i < known.len() {
+                let (index, node) = &known[i];
+                let parent = if i + 1 < known.len() && known[i + 1].0 == index ^ 1 {
+                    i += 1;
+                    hash_node::<K>(node, &known[i].1)
+                } else {
+                    let Some(sibling) = siblings.next() else {
+                        return false;
+                    };
+                    if index & 1 == 0 { hash_node::<K>(node, sibling) } else { hash_node::<K>(sibling, node) }
+                };
+
+                next.push((index / 2, parent));
+                i += 1;
+            }
+            known = next;
+        }
+
+        siblings.next().is_none() && known.len() == 1 && known[0].1 == root
+    }
+}
+
+fn depth(num_leaves: usize) -> usize {
+    num_leaves.next_power_of_two().trailing_zeros() as usize
+}
+
+fn sorted_unique(mut indices: Vec<usize>) -> Vec<usize> {
+    indices.sort();
+    indices.dedup();
+
+    indices
+}
+
+fn hash_leaf<K: HashTrait + Default, F: PrimeField>(leaf: &[F]) -> Vec<u8> {
+    let mut hasher = K::default();
+    hasher.append(&[LEAF_PREFIX]);
+    hasher.append(&to_bytes(leaf));
+
+    hasher.generate_hash()
+}
+
+fn hash_node<K: HashTrait + Default>(left: &[u8], right: &[u8]) -> Vec<u8> {
+    let mut hasher = K::default();
+    hasher.append(&[NODE_PREFIX]);
+    hasher.append(left);
+    hasher.append(right);
+
+    hasher.generate_hash()
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_bn254::Fr;
+    use sha3::Keccak256;
+
+    fn elements(n: u64) -> Vec<Fr> {
+        (0..n).map(|i| Fr::from(i * i + 7)).collect()
+    }
+
+    #[test]
+    fn test_single_openings() {
+        // 11 leaves are padded to 16
+        let leaves = elements(11);
+        let tree = MerkleTree::<Keccak256, Fr>::from_elements(&leaves);
+        let root = tree.root();
+
+        for (index, leaf) in leaves.iter().enumerate() {
+            let proof = tree.open(index);
+            assert_eq!(proof.siblings.len(), 4);
+            assert!(MerkleTree::<Keccak256, Fr>::verify(&root, 11, index, &[*leaf], &proof));
+        }
+
+        let proof = tree.open(3);
+        assert!(!MerkleTree::<Keccak256, Fr>::verify(&root, 11, 3, &[leaves[4]], &proof));
+        assert!(!MerkleTree::<Keccak256, Fr>::verify(&root, 11, 4, &[leaves[3]], &proof));
+        assert!(!MerkleTree::<Keccak256, Fr>::verify(&root, 11, 11, &[Fr::from(0)], &tree.open(10)));
+    }
+
+    #[test]
+    fn test_multiproof() {
+        let leaves = elements(16);
+        let tree = MerkleTree::<Keccak256, Fr>::from_elements(&leaves);
+        let root = tree.root();
+        let opened = |indices: &[usize]| indices.iter().map(|index| (*index, vec![leaves[*index]])).collect::<Vec<_>>();
+
+        // siblings 0 and 1 share every node above them, so only 3 nodes are needed
+        let proof = tree.open_many(&[0, 1]);
+        assert_eq!(proof.siblings.len(), 3);
+        assert!(MerkleTree::<Keccak256, Fr>::verify_many(&root, 16, &opened(&[0, 1]), &proof));
+
+        let indices = [13, 2, 3, 7, 13];
+        let proof = tree.open_many(&indices);
+        assert!(proof.siblings.len() < 4 * 4);
+        assert!(MerkleTree::<Keccak256, Fr>::verify_many(&root, 16, &opened(&indices), &proof));
+
+        let mut wrong = opened(&indices);
+        wrong[1].1[0] += Fr::from(1);
+        assert!(!MerkleTree::<Keccak256, Fr>::verify_many(&root, 16, &wrong, &proof));
+        assert!(!MerkleTree::<Keccak256, Fr>::verify_many(&root, 16, &opened(&[2, 3, 7]), &proof));
+    }
+
+    #[cfg(feature = "blake3")]
+    #[test]
+    fn test_vector_leaves() {
+        // a column per leaf, as when committing to the rows of a matrix column-wise
+        let columns: Vec<Vec<Fr>> = (0..8).map(|i| elements(4).iter().map(|value| *value + Fr::from(i)).collect()).collect();
+        let tree = MerkleTree::<blake3::Hasher, Fr>::new(&columns);
+
+        let proof = tree.open_many(&[1, 6]);
+        let opened = vec![(1, columns[1].clone()), (6, columns[6].clone())];
+        assert!(MerkleTree::<blake3::Hasher, Fr>::verify_many(&tree.root(), 8, &opened, &proof));
+        assert_ne!(tree.root(), MerkleTree::<Keccak256, Fr>::new(&columns).root());
+    }
+}
@@ -2,4 +2,17 @@ pub mod multilinear_polynomial;
 pub mod sumcheck_implementation;
 pub mod circuit;
 pub mod transcript;
-pub mod partial_sumcheck;
\ No newline at end of file
+pub mod transcript_log;
+pub mod channel;
+pub mod partial_sumcheck;
+pub mod data_parallel_gkr;
+pub mod r1cs;
+pub mod multilinear_commitment;
+pub mod spartan;
+pub mod qap;
+pub mod groth16;
+pub mod poseidon_transcript;
+pub mod merkle_tree;
+pub mod fri;
+pub mod ligero;
+pub mod hyrax;
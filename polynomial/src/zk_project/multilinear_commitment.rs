@@ -0,0 +1,71 @@
+use ark_ff::PrimeField;
+
+use super::{
+    multilinear_polynomial::MultilinearPoly,
+    partial_sumcheck::to_bytes,
+    transcript::{HashTrait, Transcript},
+};
+
+// A polynomial commitment scheme for multilinear polynomials given by their evaluations over the
+// boolean hypercube. Openings run on the caller's transcript so they can take part in a larger proof.
+pub trait MultilinearCommitment<F: PrimeField> {
+    type Commitment: Clone;
+    // whatever the prover keeps from committing and needs again to open
+    type ProverData;
+    type Proof: Clone;
+
+    fn commit(&self, poly: &MultilinearPoly<F>) -> (Self::Commitment, Self::ProverData);
+
+    // bytes the commitment is absorbed into a transcript with
+    fn commitment_bytes(commitment: &Self::Commitment) -> Vec<u8>;
+
+    fn open<K: HashTrait>(&self, poly: &MultilinearPoly<F>, prover_data: &Self::ProverData, point: &[F], transcript: &mut Transcript<K, F>) -> Self::Proof;
+
+    fn verify<K: HashTrait>(&self, commitment: &Self::Commitment, point: &[F], value: F, proof: &Self::Proof, transcript: &mut Transcript<K, F>) -> bool;
+}
+
+// The commitment is the polynomial itself and the verifier evaluates it.
+// Not succinct and not hiding, it only stands in until a real scheme is plugged in.
+pub struct TrivialCommitment;
+
+impl <F: PrimeField> MultilinearCommitment<F> for TrivialCommitment {
+    type Commitment = Vec<F>;
+    type ProverData = ();
+    type Proof = ();
+
+    fn commit(&self, poly: &MultilinearPoly<F>) -> (Self::Commitment, Self::ProverData) {
+        (poly.coefficients.clone(), ())
+    }
+
+    fn commitment_bytes(commitment: &Self::Commitment) -> Vec<u8> {
+        to_bytes(commitment)
+    }
+
+    fn open<K: HashTrait>(&self, _poly: &MultilinearPoly<F>, _prover_data: &Self::ProverData, _point: &[F], _transcript: &mut Transcript<K, F>) -> Self::Proof {}
+
+    fn verify<K: HashTrait>(&self, commitment: &Self::Commitment, point: &[F], value: F, _proof: &Self::Proof, _transcript: &mut Transcript<K, F>) -> bool {
+        commitment.len() == 1 << point.len() && MultilinearPoly::new(commitment.clone()).evaluate(&point.to_vec()) == value
+    }
+}
+
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use ark_bn254::Fq;
+    use sha3::{Keccak256, Digest};
+
+    #[test]
+    fn test_trivial_commitment() {
+        let poly = MultilinearPoly::new(vec![Fq::from(0), Fq::from(3), Fq::from(2), Fq::from(5)]);
+        let point = vec![Fq::from(4), Fq::from(7)];
+        let value = poly.evaluate(&point);
+
+        let (commitment, prover_data) = TrivialCommitment.commit(&poly);
+        #[allow(clippy::let_unit_value)]
+        let proof = TrivialCommitment.open(&poly, &prover_data, &point, &mut Transcript::new(b"test", Keccak256::new()));
+
+        assert!(TrivialCommitment.verify(&commitment, &point, value, &proof, &mut Transcript::new(b"test", Keccak256::new())));
+        assert!(!TrivialCommitment.verify(&commitment, &point, value + Fq::from(1), &proof, &mut Transcript::new(b"test", Keccak256::new())));
+    }
+}
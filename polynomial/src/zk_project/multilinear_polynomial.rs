@@ -39,7 +39,7 @@ impl<F: PrimeField> MultilinearPoly<F> {
     pub fn evaluate(&self, values: &Vec<F>) -> F {
         let mut poly = self.clone();
         for i in 0..values.len(){
-            poly = self.partial_evaluate((0, values[i]));
+            poly = poly.partial_evaluate((0, values[i]));
         }
         poly.coefficients[0]
     }
@@ -77,6 +77,25 @@ impl<F: PrimeField> MultilinearPoly<F> {
    
 }
 
+// Evaluations of eq(point, x) over the boolean hypercube, in the same variable order as `evaluate`
+// (the first coordinate of `point` is the most significant bit of the index).
+pub fn eq_evaluations<F: PrimeField>(point: &[F]) -> Vec<F> {
+    let mut evaluations = vec![F::one()];
+
+    for r in point.iter() {
+        evaluations = evaluations.iter().flat_map(|e| [*e * (F::one() - r), *e * r]).collect();
+    }
+
+    evaluations
+}
+
+// eq(a, b) = prod (a_i * b_i + (1 - a_i) * (1 - b_i))
+pub fn eq_eval<F: PrimeField>(a: &[F], b: &[F]) -> F {
+    assert!(a.len() == b.len(), "points must have the same number of variables");
+
+    a.iter().zip(b.iter()).map(|(x, y)| *x * y + (F::one() - x) * (F::one() - y)).product()
+}
+
 fn main() {
     fn get_unique_pairs_coefficients(arr: Vec<i32>, pos: usize) -> Vec<(i32, i32)> {
         let mask = 1 << pos; // Mask for the current bit position
@@ -196,6 +215,30 @@ mod test {
         );
         //
     }
+
+    #[test]
+    fn test_evaluate() {
+        // f(x1, x2, x3) = 3(1 - x1)x2x3 + 2x1x2(1 - x3) + 5x1x2x3
+        let poly = create_multilinear_poly();
+        assert_eq!(poly.evaluate(&vec![Fq::from(1), Fq::from(1), Fq::from(1)]), Fq::from(5));
+        assert_eq!(poly.evaluate(&vec![Fq::from(0), Fq::from(1), Fq::from(1)]), Fq::from(3));
+        assert_eq!(poly.evaluate(&vec![Fq::from(2), Fq::from(3), Fq::from(1)]), Fq::from(21));
+    }
+
+    #[test]
+    fn test_eq_evaluations() {
+        let point = vec![Fq::from(3), Fq::from(5)];
+        let evaluations = eq_evaluations(&point);
+
+        assert_eq!(evaluations.len(), 4);
+        assert_eq!(evaluations.iter().sum::<Fq>(), Fq::from(1));
+        assert_eq!(evaluations[2], eq_eval(&point, &[Fq::from(1), Fq::from(0)]));
+
+        let poly = create_multilinear_poly();
+        let r = vec![Fq::from(7), Fq::from(11), Fq::from(13)];
+        let expected: Fq = eq_evaluations(&r).iter().zip(poly.coefficients.iter()).map(|(e, c)| *e * c).sum();
+        assert_eq!(poly.evaluate(&r), expected);
+    }
 }
 
     
\ No newline at end of file
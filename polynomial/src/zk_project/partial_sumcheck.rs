@@ -1,50 +1,141 @@
-use ark_bn254::Fq;
-use ark_ff::{BigInteger, Field, PrimeField};
-use ark_std::rand::Rng;
-use sha2::{Sha256, Digest};
-use super::{circuit::SumPoly, multilinear_polynomial::MultilinearPoly, transcript::{HashTrait, Transcript}};
-const PRIME: u64 = 23; 
+use ark_ff::{BigInteger, PrimeField};
+use crate::interpolation::lagrange_interpolation;
+use super::{channel::Channel, circuit::SumPoly, transcript::{HashTrait, Transcript}};
 
 
 
-fn prover<F : PrimeField, K: HashTrait>(initial_poly:SumPoly<F>, num_vars: usize, hash_function: K) -> (F, Vec<Vec<F>>) {
+// Sumcheck over a sum of products of multilinear polynomials.
+// Every round polynomial is sent as its evaluations at 0, 1, ..., degree.
+pub fn prover<F : PrimeField, K: HashTrait>(initial_poly: SumPoly<F>, num_vars: usize, hash_function: K) -> (F, Vec<Vec<F>>) {
+
+    prove_with_transcript(initial_poly, num_vars, &mut Transcript::new(b"partial-sumcheck", hash_function))
+}
+
+pub fn prove_with_transcript<F : PrimeField, K: HashTrait>(initial_poly: SumPoly<F>, num_vars: usize, transcript: &mut Transcript<K, F>) -> (F, Vec<Vec<F>>) {
 
     let claimed_sum: F = compute_sum(&initial_poly);
 
+    transcript.append_field_element(b"claimed_sum", &claimed_sum);
+
+    transcript.append_field_elements(b"polynomial", &initial_poly.reduce());
+
+    let (proof, _) = prove_rounds(initial_poly, num_vars, transcript);
+
+    (claimed_sum, proof)
+}
+
+// Runs the sumcheck rounds on a channel owned by the caller, so the sumcheck can be embedded in a
+// larger protocol. Returns the round polynomials and the challenges they were reduced with.
+pub fn prove_rounds<F : PrimeField, C: Channel<F>>(initial_poly: SumPoly<F>, num_vars: usize, channel: &mut C) -> (Vec<Vec<F>>, Vec<F>) {
+
     let mut proof = vec![];
-    let mut transcript = Transcript::init(hash_function);
+    let mut prover = SumcheckProver::new(initial_poly);
+
+    for _ in 0..num_vars {
 
-    transcript.absorb(&claimed_sum.into_bigint().to_bytes_be());
+        let round_poly = prover.round_message();
 
-    transcript.absorb(&to_bytes(&initial_poly.polys));
+        channel.send(b"round_poly", &round_poly);
 
-    let mut poly = initial_poly;
-    
-    for round in 0..num_vars {
-        
-        let round_poly = generate_round_poly(&poly);
-        
-        transcript.absorb(&to_bytes(&round_poly));
-        
         proof.push(round_poly);
 
-        let challenge = transcript.squeeze();
-        dbg!(&challenge);
+        prover.receive_challenge(channel.challenge(b"challenge"));
+    }
 
-       poly = poly.partial_evaluate((0, challenge));
+    (proof, prover.challenges)
+}
 
+// Prover side of the sumcheck as a state machine: answer with a round polynomial, receive a challenge, repeat.
+pub struct SumcheckProver<F: PrimeField> {
+    poly: SumPoly<F>,
+    pub challenges: Vec<F>
+}
 
+impl <F: PrimeField> SumcheckProver<F> {
+    pub fn new(poly: SumPoly<F>) -> Self {
+        Self { poly, challenges: vec![] }
+    }
+
+    pub fn round_message(&self) -> Vec<F> {
+        generate_round_poly(&self.poly)
+    }
+
+    pub fn receive_challenge(&mut self, challenge: F) {
+        self.poly = self.poly.partial_evaluate(challenge);
+        self.challenges.push(challenge);
     }
-    
-    (claimed_sum, proof)
 }
 
+// Verifier side of the sumcheck. It never sees the polynomial: once every round is done it hands back
+// the claim that an evaluation of the polynomial at the challenges has to match.
+pub struct SumcheckVerifier<F: PrimeField> {
+    num_vars: usize,
+    degree: usize,
+    running_claim: F,
+    round_poly: Option<Vec<F>>,
+    pub challenges: Vec<F>
+}
 
+impl <F: PrimeField> SumcheckVerifier<F> {
+    pub fn new(claimed_sum: F, num_vars: usize, degree: usize) -> Self {
+        Self { num_vars, degree, running_claim: claimed_sum, round_poly: None, challenges: vec![] }
+    }
 
+    // false when the round polynomial is malformed, unexpected or inconsistent with the running claim
+    pub fn receive_round(&mut self, round_poly: &[F]) -> bool {
+        if self.round_poly.is_some() || self.challenges.len() == self.num_vars || round_poly.len() != self.degree + 1 {
+            return false;
+        }
 
+        if self.running_claim != round_poly[0] + round_poly[1] {
+            return false;
+        }
 
+        self.round_poly = Some(round_poly.to_vec());
+        true
+    }
+
+    pub fn receive_challenge(&mut self, challenge: F) {
+        let round_poly = self.round_poly.take().expect("a challenge must follow a round polynomial");
+
+        self.running_claim = evaluate_round_poly(&round_poly, challenge);
+        self.challenges.push(challenge);
+    }
+
+    // the final claim and the challenges, None until every round is done
+    pub fn final_claim(self) -> Option<(F, Vec<F>)> {
+        (self.challenges.len() == self.num_vars).then_some((self.running_claim, self.challenges))
+    }
+}
+
+// Runs prover and verifier against each other on one channel. With an `InteractiveChannel` the
+// challenges are real verifier randomness, which allows soundness experiments with false claims.
+pub fn run_interactive<F: PrimeField, C: Channel<F>>(claimed_sum: F, poly: SumPoly<F>, num_vars: usize, channel: &mut C) -> bool {
+
+    let mut verifier = SumcheckVerifier::new(claimed_sum, num_vars, poly.get_degree());
+    let mut prover = SumcheckProver::new(poly.clone());
+
+    for _ in 0..num_vars {
+        let round_poly = prover.round_message();
+        if !verifier.receive_round(&round_poly) {
+            return false;
+        }
+        channel.send(b"round_poly", &round_poly);
 
- fn to_bytes<F:PrimeField>(values: &Vec<F>) -> Vec<u8>{
+        let challenge = channel.challenge(b"challenge");
+        prover.receive_challenge(challenge);
+        verifier.receive_challenge(challenge);
+    }
+
+    match verifier.final_claim() {
+        Some((final_claim, challenges)) => final_claim == poly.evaluate(challenges),
+        None => false
+    }
+}
+
+
+
+ pub(crate) fn to_bytes<F:PrimeField>(values: &[F]) -> Vec<u8>{
     let mut result = vec![];
     for value in values{
        result.extend(value.into_bigint().to_bytes_be());
@@ -55,52 +146,173 @@ fn prover<F : PrimeField, K: HashTrait>(initial_poly:SumPoly<F>, num_vars: usize
 
 
 
-  
-   fn generate_round_poly<F:PrimeField>(poly: &SumPoly<F>) ->Vec<F>{
-    let eval_zero = poly.partial_evaluate(F::zero()).reduce().iter().sum();
-    let eval_one = poly.partial_evaluate(F::one()).reduce().iter().sum();
-    let eval_two = poly.partial_evaluate(F::from(2_u64)).reduce().iter().sum();
+ fn generate_round_poly<F:PrimeField>(poly: &SumPoly<F>) ->Vec<F>{
+    (0..=poly.get_degree())
+        .map(|point| poly.partial_evaluate(F::from(point as u64)).reduce().iter().sum())
+        .collect()
+ }
 
-    vec![eval_zero,eval_one, eval_two]
+ // evaluates a round polynomial given by its evaluations at 0, 1, ..., degree
+ pub(crate) fn evaluate_round_poly<F: PrimeField>(round_poly: &[F], x: F) -> F {
+    let points: Vec<(F, F)> = round_poly.iter().enumerate().map(|(i, y)| (F::from(i as u64), *y)).collect();
+
+    lagrange_interpolation(&points, x)
  }
 
- fn compute_sum<F: PrimeField>(poly: &MultilinearPoly<F>) -> F {
-    poly.coefficients.reduce().iter().sum()
+ fn compute_sum<F: PrimeField>(poly: &SumPoly<F>) -> F {
+    poly.reduce().iter().sum()
  }
 
 
-fn verifier<F : PrimeField, K: HashTrait>(claimed_sum: F, proof: &Vec<Vec<F>>, num_vars: usize , initial_poly: &SumPoly<F>, hash_function: K) -> bool {
-    
-    dbg!("Verifier");
+pub fn verifier<F : PrimeField, K: HashTrait>(claimed_sum: F, proof: &[Vec<F>], num_vars: usize , initial_poly: &SumPoly<F>, hash_function: K) -> bool {
+
+    verify_with_transcript(claimed_sum, proof, num_vars, initial_poly, &mut Transcript::new(b"partial-sumcheck", hash_function))
+}
+
+pub fn verify_with_transcript<F : PrimeField, K: HashTrait>(claimed_sum: F, proof: &[Vec<F>], num_vars: usize , initial_poly: &SumPoly<F>, transcript: &mut Transcript<K, F>) -> bool {
+
+    transcript.append_field_element(b"claimed_sum", &claimed_sum);
+    transcript.append_field_elements(b"polynomial", &initial_poly.reduce());
 
-    let mut transcript = Transcript:: init(hash_function);
-    transcript.absorb(&claimed_sum.into_bigint().to_bytes_be());
-    transcript.absorb(&to_bytes(&initial_poly.coefficients));
+    match verify_rounds(claimed_sum, proof, num_vars, initial_poly.get_degree(), transcript) {
+        Some((running_claim, challenges)) => running_claim == initial_poly.evaluate(challenges),
+        None => false
+    }
+}
 
-    let mut running_claim= claimed_sum;
-    let mut challenges =vec![];
+// Checks the rounds of a sumcheck proof without access to the polynomial.
+// Returns the claim the final evaluation has to match together with the challenges,
+// or None when a round polynomial is malformed or inconsistent with the running claim.
+pub fn verify_rounds<F : PrimeField, C: Channel<F>>(claimed_sum: F, proof: &[Vec<F>], num_vars: usize, degree: usize, channel: &mut C) -> Option<(F, Vec<F>)> {
+
+    if proof.len() != num_vars {
+        return None;
+    }
+
+    let mut verifier = SumcheckVerifier::new(claimed_sum, num_vars, degree);
 
     for round_poly in proof {
-        if round_poly.len() !=2 {
-           return false;
-        } 
-        assert!(running_claim == round_poly.iter().sum());
-        transcript.absorb(&to_bytes(round_poly));
+        if !verifier.receive_round(round_poly) {
+            return None;
+        }
+
+        channel.send(b"round_poly", round_poly);
+
+        verifier.receive_challenge(channel.challenge(b"challenge"));
+    }
 
-        let challenge = transcript.squeeze();
-        challenges.push(challenge);
+    verifier.final_claim()
+}
+
+
+#[cfg(test)]
+mod test {
+    use super::{prove_with_transcript, prover, run_interactive, verifier, verify_with_transcript, SumcheckVerifier};
+    use crate::zk_project::channel::InteractiveChannel;
+    use rand::{rngs::StdRng, SeedableRng};
+    use crate::zk_project::{circuit::{ProductPoly, SumPoly}, transcript::{EvmKeccak256, HashTrait, Transcript}, transcript_log::{first_divergence, TranscriptEvent}};
+    use ark_bn254::Fq;
+    use sha3::{Keccak256, Digest};
+
+    // f(x1, x2) = a(x1, x2) * b(x1, x2) + c(x1, x2) * d(x1, x2)
+    fn create_test_polynomial() -> SumPoly<Fq> {
+        let a = vec![Fq::from(0), Fq::from(3), Fq::from(2), Fq::from(5)];
+        let b = vec![Fq::from(1), Fq::from(4), Fq::from(6), Fq::from(2)];
+        let c = vec![Fq::from(7), Fq::from(0), Fq::from(1), Fq::from(3)];
+        let d = vec![Fq::from(2), Fq::from(2), Fq::from(9), Fq::from(1)];
+
+        SumPoly::new(vec![ProductPoly::new(vec![a, b]), ProductPoly::new(vec![c, d])])
+    }
+
+    #[test]
+    fn test_partial_sumcheck_protocol() {
+        let poly = create_test_polynomial();
+
+        let (claimed_sum, proof) = prover(create_test_polynomial(), 2, Keccak256::new());
+
+        // 0*1 + 3*4 + 2*6 + 5*2 + 7*2 + 0*2 + 1*9 + 3*1
+        assert_eq!(claimed_sum, Fq::from(60));
+        assert!(proof.iter().all(|round_poly| round_poly.len() == 3));
+        assert!(verifier(claimed_sum, &proof, 2, &poly, Keccak256::new()));
+    }
 
-        dbg!(&challenge);
+    #[test]
+    fn test_partial_sumcheck_rejects_wrong_sum() {
+        let poly = create_test_polynomial();
 
-         running_claim = round_poly[0] + challenge * (round_poly[1] - round_poly[0]);
+        let (claimed_sum, proof) = prover(create_test_polynomial(), 2, Keccak256::new());
 
-         dbg!(&running_claim);
-        
+        assert!(!verifier(claimed_sum + Fq::from(1), &proof, 2, &poly, Keccak256::new()));
     }
-    let final_eval = initial_poly.evaluate(&challenges);
 
-    dbg!(&final_eval);
-    dbg!(&running_claim);
+    #[test]
+    fn test_partial_sumcheck_rejects_tampered_round() {
+        let poly = create_test_polynomial();
 
-    running_claim == final_eval
-}
\ No newline at end of file
+        let (claimed_sum, mut proof) = prover(create_test_polynomial(), 2, Keccak256::new());
+        proof[1][0] += Fq::from(1);
+        proof[1][1] -= Fq::from(1);
+
+        assert!(!verifier(claimed_sum, &proof, 2, &poly, Keccak256::new()));
+    }
+
+    fn prove_and_verify_with<K: HashTrait>(new_hash: impl Fn() -> K) -> bool {
+        let poly = create_test_polynomial();
+        let (claimed_sum, proof) = prover(create_test_polynomial(), 2, new_hash());
+
+        verifier(claimed_sum, &proof, 2, &poly, new_hash())
+    }
+
+    #[test]
+    fn test_partial_sumcheck_with_every_backend() {
+        assert!(prove_and_verify_with(Keccak256::new));
+        assert!(prove_and_verify_with(EvmKeccak256::new));
+        #[cfg(feature = "sha256")]
+        assert!(prove_and_verify_with(sha2::Sha256::new));
+        #[cfg(feature = "blake2")]
+        assert!(prove_and_verify_with(blake2::Blake2s256::new));
+        #[cfg(feature = "blake3")]
+        assert!(prove_and_verify_with(blake3::Hasher::new));
+    }
+
+    #[test]
+    fn test_partial_sumcheck_transcripts_agree() {
+        let poly = create_test_polynomial();
+
+        let mut prover_transcript = Transcript::new_recording(b"partial-sumcheck", Keccak256::new());
+        let (claimed_sum, mut proof) = prove_with_transcript(create_test_polynomial(), 2, &mut prover_transcript);
+
+        let mut verifier_transcript = Transcript::new_recording(b"partial-sumcheck", Keccak256::new());
+        assert!(verify_with_transcript(claimed_sum, &proof, 2, &poly, &mut verifier_transcript));
+        assert_eq!(first_divergence(prover_transcript.log().unwrap(), verifier_transcript.log().unwrap()), None);
+
+        // a consistent change to the second round polynomial is first seen when it is absorbed
+        proof[1][0] += Fq::from(1);
+        proof[1][1] -= Fq::from(1);
+
+        let mut verifier_transcript = Transcript::new_recording(b"partial-sumcheck", Keccak256::new());
+        assert!(!verify_with_transcript(claimed_sum, &proof, 2, &poly, &mut verifier_transcript));
+
+        let divergence = first_divergence(prover_transcript.log().unwrap(), verifier_transcript.log().unwrap()).unwrap();
+        assert_eq!(divergence.index, 6);
+        assert!(matches!(divergence.verifier, Some(TranscriptEvent::Append { label, .. }) if label == b"round_poly"));
+    }
+
+    #[test]
+    fn test_partial_sumcheck_interactive() {
+        let mut channel = InteractiveChannel::new(StdRng::seed_from_u64(0));
+
+        assert!(run_interactive(Fq::from(60), create_test_polynomial(), 2, &mut channel));
+        assert!(!run_interactive(Fq::from(61), create_test_polynomial(), 2, &mut channel));
+        assert_eq!(channel.messages_sent, 2);
+
+        // a prover shifting its first round polynomial to a false sum passes the sum check of that round,
+        // and the verifier refuses a second round polynomial before it has sent a challenge
+        let mut verifier = SumcheckVerifier::new(Fq::from(61), 2, 2);
+        let honest_round = super::SumcheckProver::new(create_test_polynomial()).round_message();
+        let cheating_round: Vec<Fq> = honest_round.iter().map(|value| *value + Fq::from(1) / Fq::from(2)).collect();
+
+        assert!(verifier.receive_round(&cheating_round));
+        assert!(!verifier.receive_round(&cheating_round));
+    }
+}
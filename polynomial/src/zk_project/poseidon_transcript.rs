@@ -0,0 +1,147 @@
+use ark_crypto_primitives::sponge::{
+    poseidon::{find_poseidon_ark_and_mds, PoseidonConfig, PoseidonSponge},
+    Absorb, CryptographicSponge,
+};
+use ark_ff::PrimeField;
+
+// x^5 S-box with 8 full and 57 partial rounds over a width 3 state, the parameters the Poseidon paper
+// gives for 128 bit security over ~255 bit fields (BN254 Fr and BLS12-381 Fr).
+const RATE: usize = 2;
+const CAPACITY: usize = 1;
+const ALPHA: u64 = 5;
+const FULL_ROUNDS: usize = 8;
+const PARTIAL_ROUNDS: usize = 57;
+
+// Round constants and MDS matrix generated with the Grain LFSR from the reference implementation.
+pub fn poseidon_config<F: PrimeField>() -> PoseidonConfig<F> {
+    let (ark, mds) = find_poseidon_ark_and_mds::<F>(F::MODULUS_BIT_SIZE as u64, RATE, FULL_ROUNDS as u64, PARTIAL_ROUNDS as u64, 0);
+
+    PoseidonConfig::new(FULL_ROUNDS, PARTIAL_ROUNDS, ALPHA, mds, ark, RATE, CAPACITY)
+}
+
+pub fn bn254_config() -> PoseidonConfig<ark_bn254::Fr> {
+    poseidon_config()
+}
+
+pub fn bls12_381_config() -> PoseidonConfig<ark_bls12_381::Fr> {
+    poseidon_config()
+}
+
+// Fiat-Shamir transcript over a Poseidon sponge, with the same labeled API as `Transcript`.
+// Field elements go into the sponge as they are, so a verifier circuit over F only pays
+// for the permutation and never for bit decompositions.
+pub struct PoseidonTranscript<F: PrimeField + Absorb> {
+    sponge: PoseidonSponge<F>
+}
+
+impl <F: PrimeField + Absorb> PoseidonTranscript<F> {
+    pub fn new(domain_separator: &[u8], config: &PoseidonConfig<F>) -> Self {
+        let mut transcript = Self { sponge: PoseidonSponge::new(config) };
+        transcript.append_message(b"dom-sep", domain_separator);
+
+        transcript
+    }
+
+    // the label and every message are prefixed with their length, as in `Transcript`
+    fn append_label(&mut self, label: &[u8]) {
+        self.sponge.absorb(&label.len());
+        self.sponge.absorb(&label);
+    }
+
+    pub fn append_message(&mut self, label: &[u8], message: &[u8]) {
+        self.append_label(label);
+        self.sponge.absorb(&message.len());
+        self.sponge.absorb(&message);
+    }
+
+    pub fn append_field_element(&mut self, label: &[u8], element: &F) {
+        self.append_label(label);
+        self.sponge.absorb(element);
+    }
+
+    pub fn append_field_elements(&mut self, label: &[u8], elements: &[F]) {
+        self.append_label(label);
+        self.sponge.absorb(&elements.len());
+        self.sponge.absorb(&elements);
+    }
+
+    pub fn challenge_scalar(&mut self, label: &[u8]) -> F {
+        self.append_label(label);
+        self.sponge.squeeze_field_elements(1)[0]
+    }
+
+    pub fn challenge_scalars(&mut self, label: &[u8], n: usize) -> Vec<F> {
+        self.append_label(label);
+        self.sponge.absorb(&n);
+        self.sponge.squeeze_field_elements(n)
+    }
+}
+
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::zk_project::{circuit::{ProductPoly, SumPoly}, partial_sumcheck::{prove_rounds, verify_rounds}};
+    use ark_bn254::Fr;
+
+    #[test]
+    fn test_poseidon_transcript_bn254() {
+        let config = bn254_config();
+        let mut transcript = PoseidonTranscript::new(b"test", &config);
+        let mut transcript1 = PoseidonTranscript::new(b"test", &config);
+
+        transcript.append_field_elements(b"xs", &[ark_bn254::Fr::from(1), ark_bn254::Fr::from(2)]);
+        transcript1.append_field_elements(b"xs", &[ark_bn254::Fr::from(1), ark_bn254::Fr::from(2)]);
+
+        let challenges = transcript.challenge_scalars(b"r", 2);
+        assert_eq!(challenges, transcript1.challenge_scalars(b"r", 2));
+        assert_ne!(challenges[0], challenges[1]);
+
+        // squeezing ratchets the sponge
+        assert_ne!(transcript.challenge_scalar(b"r"), challenges[0]);
+    }
+
+    #[test]
+    fn test_poseidon_transcript_bls12_381() {
+        let config = bls12_381_config();
+        let mut transcript = PoseidonTranscript::new(b"test", &config);
+        let mut transcript1 = PoseidonTranscript::new(b"test", &config);
+
+        transcript.append_field_element(b"x", &ark_bls12_381::Fr::from(7));
+        transcript1.append_field_element(b"x", &ark_bls12_381::Fr::from(8));
+
+        assert_ne!(transcript.challenge_scalar(b"r"), transcript1.challenge_scalar(b"r"));
+    }
+
+    #[test]
+    fn test_poseidon_domain_separation_and_framing() {
+        let config = bn254_config();
+
+        let mut transcript = PoseidonTranscript::new(b"protocol-a", &config);
+        let mut transcript1 = PoseidonTranscript::new(b"protocol-b", &config);
+        assert_ne!(transcript.challenge_scalar(b"r"), transcript1.challenge_scalar(b"r"));
+
+        let mut transcript = PoseidonTranscript::new(b"test", &config);
+        let mut transcript1 = PoseidonTranscript::new(b"test", &config);
+        transcript.append_message(b"a", b"bc");
+        transcript1.append_message(b"ab", b"c");
+        assert_ne!(transcript.challenge_scalar(b"r"), transcript1.challenge_scalar(b"r"));
+    }
+
+    #[test]
+    fn test_sumcheck_over_poseidon() {
+        let config = bn254_config();
+        let a: Vec<Fr> = [0, 3, 2, 5].into_iter().map(Fr::from).collect();
+        let b: Vec<Fr> = [1, 4, 6, 2].into_iter().map(Fr::from).collect();
+        let poly = SumPoly::new(vec![ProductPoly::new(vec![a, b])]);
+        let claimed_sum: Fr = poly.reduce().iter().sum();
+
+        let (proof, prover_challenges) = prove_rounds(poly.clone(), 2, &mut PoseidonTranscript::new(b"sumcheck", &config));
+
+        let (final_claim, challenges) = verify_rounds(claimed_sum, &proof, 2, 2, &mut PoseidonTranscript::new(b"sumcheck", &config)).unwrap();
+        assert_eq!(challenges, prover_challenges);
+        assert_eq!(final_claim, poly.evaluate(challenges));
+
+        assert!(verify_rounds(claimed_sum + Fr::from(1), &proof, 2, 2, &mut PoseidonTranscript::new(b"sumcheck", &config)).is_none());
+    }
+}
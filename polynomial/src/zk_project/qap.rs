@@ -0,0 +1,130 @@
+use ark_ff::PrimeField;
+use univariate::{ntt::NttDomain, univariate::UnivariateDense};
+
+use super::r1cs::R1CS;
+
+// Quadratic arithmetic program of an R1CS instance.
+//
+// Constraint i is attached to the i-th element of an NTT domain and u_j, v_j, w_j interpolate column j
+// of A, B and C over the domain. z satisfies the R1CS exactly when
+//     (sum z_j u_j) * (sum z_j v_j) - (sum z_j w_j) = h * (X^n - 1)
+// for some polynomial h.
+//
+// Like arkworks, one extra constraint z_j * 0 = 0 is added for the constant and every public input,
+// which keeps the u_j of the public variables linearly independent as Groth16 needs.
+pub struct QAP<F: PrimeField> {
+    pub domain: NttDomain<F>,
+    pub u: Vec<UnivariateDense<F>>,
+    pub v: Vec<UnivariateDense<F>>,
+    pub w: Vec<UnivariateDense<F>>,
+    pub num_public: usize
+}
+
+impl <F: PrimeField> QAP<F> {
+    pub fn from_r1cs(r1cs: &R1CS<F>) -> Self {
+        let num_variables = r1cs.num_variables();
+        let num_rows = r1cs.num_constraints() + 1 + r1cs.num_public;
+        let domain = NttDomain::new(num_rows).expect("field has no NTT domain large enough for this R1CS");
+
+        let mut a_columns = vec![vec![F::zero(); domain.size]; num_variables];
+        let mut b_columns = vec![vec![F::zero(); domain.size]; num_variables];
+        let mut c_columns = vec![vec![F::zero(); domain.size]; num_variables];
+
+        for (matrix, columns) in [(&r1cs.a, &mut a_columns), (&r1cs.b, &mut b_columns), (&r1cs.c, &mut c_columns)] {
+            for (row_index, row) in matrix.rows.iter().enumerate() {
+                for (col, value) in row.iter() {
+                    columns[*col][row_index] += value;
+                }
+            }
+        }
+
+        for (variable, column) in a_columns.iter_mut().enumerate().take(1 + r1cs.num_public) {
+            column[r1cs.num_constraints() + variable] = F::one();
+        }
+
+        let interpolate = |columns: Vec<Vec<F>>| columns.iter().map(|column| domain.interpolate(column)).collect();
+
+        Self {
+            u: interpolate(a_columns),
+            v: interpolate(b_columns),
+            w: interpolate(c_columns),
+            domain,
+            num_public: r1cs.num_public
+        }
+    }
+
+    pub fn num_variables(&self) -> usize {
+        self.u.len()
+    }
+
+    // sum z_j p_j
+    pub fn combine(polys: &[UnivariateDense<F>], z: &[F]) -> UnivariateDense<F> {
+        polys.iter().zip(z.iter()).fold(UnivariateDense::zero(), |acc, (poly, z_j)| &acc + &poly.scalar_mul(*z_j))
+    }
+
+    // h = (A * B - C) / (X^n - 1), or None when z does not satisfy the constraints
+    pub fn h_polynomial(&self, z: &[F]) -> Option<UnivariateDense<F>> {
+        assert!(z.len() == self.num_variables(), "wrong number of variables");
+
+        let a = Self::combine(&self.u, z);
+        let b = Self::combine(&self.v, z);
+        let c = Self::combine(&self.w, z);
+
+        let p = &NttDomain::multiply(&a, &b) - &c;
+        let (h, remainder) = p.divide_by_vanishing(self.domain.size);
+
+        remainder.is_zero().then_some(h)
+    }
+
+    pub fn is_satisfied(&self, z: &[F]) -> bool {
+        self.h_polynomial(z).is_some()
+    }
+}
+
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use ark_bls12_381::Fr;
+
+    // x^3 + x + 5 = out, with witness (x, x^2, x^3)
+    fn create_cubic_r1cs() -> R1CS<Fr> {
+        let mut r1cs = R1CS::new(1, 3);
+        let (out, x, x_squared, x_cubed) = (r1cs.public_variable(0), r1cs.witness_variable(0), r1cs.witness_variable(1), r1cs.witness_variable(2));
+
+        r1cs.add_constraint(vec![(x, Fr::from(1))], vec![(x, Fr::from(1))], vec![(x_squared, Fr::from(1))]);
+        r1cs.add_constraint(vec![(x_squared, Fr::from(1))], vec![(x, Fr::from(1))], vec![(x_cubed, Fr::from(1))]);
+        r1cs.add_constraint(vec![(x_cubed, Fr::from(1)), (x, Fr::from(1)), (0, Fr::from(5))], vec![(0, Fr::from(1))], vec![(out, Fr::from(1))]);
+
+        r1cs
+    }
+
+    #[test]
+    fn test_qap_from_r1cs() {
+        let r1cs = create_cubic_r1cs();
+        let qap = QAP::from_r1cs(&r1cs);
+
+        // 3 constraints and 2 public input constraints
+        assert_eq!(qap.domain.size, 8);
+        assert_eq!(qap.num_variables(), 5);
+
+        // u_j interpolates column j of A over the domain
+        let x = r1cs.witness_variable(0);
+        assert_eq!(qap.u[x].evaluate(qap.domain.element(0)), Fr::from(1));
+        assert_eq!(qap.u[x].evaluate(qap.domain.element(1)), Fr::from(0));
+        assert_eq!(qap.u[x].evaluate(qap.domain.element(2)), Fr::from(1));
+    }
+
+    #[test]
+    fn test_qap_satisfiability() {
+        let r1cs = create_cubic_r1cs();
+        let qap = QAP::from_r1cs(&r1cs);
+
+        let z = r1cs.z_vector(&[Fr::from(35)], &[Fr::from(3), Fr::from(9), Fr::from(27)]);
+        let h = qap.h_polynomial(&z).unwrap();
+        assert!(h.degree() <= qap.domain.size - 2);
+
+        let wrong_z = r1cs.z_vector(&[Fr::from(35)], &[Fr::from(3), Fr::from(9), Fr::from(26)]);
+        assert!(!qap.is_satisfied(&wrong_z));
+    }
+}
@@ -0,0 +1,307 @@
+use std::collections::BTreeMap;
+
+use ark_ff::PrimeField;
+
+use super::circuit::{Circuit, Operator};
+
+// A matrix stored row by row, every row holding only its non-zero (column, value) entries.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SparseMatrix<F: PrimeField> {
+    pub num_cols: usize,
+    pub rows: Vec<Vec<(usize, F)>>
+}
+
+// Rank-1 constraint system A z ∘ B z = C z over z = (1, public inputs, witness).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct R1CS<F: PrimeField> {
+    pub a: SparseMatrix<F>,
+    pub b: SparseMatrix<F>,
+    pub c: SparseMatrix<F>,
+    pub num_public: usize,
+    pub num_witness: usize
+}
+
+type LinearCombination<F> = BTreeMap<usize, F>;
+
+// (public inputs, witness)
+type Assignment<F> = (Vec<F>, Vec<F>);
+
+impl <F: PrimeField> SparseMatrix<F> {
+    pub fn new(num_cols: usize) -> Self {
+        Self { num_cols, rows: Vec::new() }
+    }
+
+    pub fn num_rows(&self) -> usize {
+        self.rows.len()
+    }
+
+    pub fn push_row(&mut self, row: Vec<(usize, F)>) {
+        assert!(row.iter().all(|(col, _)| *col < self.num_cols), "column index out of bounds");
+
+        self.rows.push(row);
+    }
+
+    pub fn multiply(&self, z: &[F]) -> Vec<F> {
+        assert!(z.len() == self.num_cols, "vector length must match the number of columns");
+
+        self.rows.iter().map(|row| row.iter().map(|(col, value)| *value * z[*col]).sum()).collect()
+    }
+}
+
+impl <F: PrimeField> R1CS<F> {
+    pub fn new(num_public: usize, num_witness: usize) -> Self {
+        let num_variables = 1 + num_public + num_witness;
+
+        Self {
+            a: SparseMatrix::new(num_variables),
+            b: SparseMatrix::new(num_variables),
+            c: SparseMatrix::new(num_variables),
+            num_public,
+            num_witness
+        }
+    }
+
+    pub fn num_variables(&self) -> usize {
+        1 + self.num_public + self.num_witness
+    }
+
+    pub fn num_constraints(&self) -> usize {
+        self.a.num_rows()
+    }
+
+    // index of the i-th public input in z
+    pub fn public_variable(&self, index: usize) -> usize {
+        assert!(index < self.num_public, "public input index out of bounds");
+        1 + index
+    }
+
+    // index of the i-th witness element in z
+    pub fn witness_variable(&self, index: usize) -> usize {
+        assert!(index < self.num_witness, "witness index out of bounds");
+        1 + self.num_public + index
+    }
+
+    // adds the constraint <a, z> * <b, z> = <c, z>
+    pub fn add_constraint(&mut self, a: Vec<(usize, F)>, b: Vec<(usize, F)>, c: Vec<(usize, F)>) {
+        self.a.push_row(a);
+        self.b.push_row(b);
+        self.c.push_row(c);
+    }
+
+    pub fn z_vector(&self, public_inputs: &[F], witness: &[F]) -> Vec<F> {
+        assert!(public_inputs.len() == self.num_public, "wrong number of public inputs");
+        assert!(witness.len() == self.num_witness, "wrong number of witness elements");
+
+        let mut z = vec![F::one()];
+        z.extend_from_slice(public_inputs);
+        z.extend_from_slice(witness);
+
+        z
+    }
+
+    pub fn is_satisfied(&self, public_inputs: &[F], witness: &[F]) -> bool {
+        if public_inputs.len() != self.num_public || witness.len() != self.num_witness {
+            return false;
+        }
+
+        let z = self.z_vector(public_inputs, witness);
+        let az = self.a.multiply(&z);
+        let bz = self.b.multiply(&z);
+        let cz = self.c.multiply(&z);
+
+        az.iter().zip(bz.iter()).zip(cz.iter()).all(|((a, b), c)| *a * b == *c)
+    }
+
+    // Compiles a layered circuit: the outputs are the public inputs, the circuit inputs and the
+    // outputs of the inner Mul gates are the witness. Every Mul gate gives one constraint, Add gates
+    // only build linear combinations, which are bound to the outputs with one constraint each.
+    pub fn from_circuit(circuit: &Circuit<F>) -> Self {
+        compile_circuit(circuit, false).0
+    }
+
+    // Public inputs and witness for `from_circuit`, read from an evaluated circuit.
+    pub fn circuit_assignment(circuit: &Circuit<F>) -> (Vec<F>, Vec<F>) {
+        assert!(!circuit.layer_evaluations.is_empty(), "circuit must be evaluated first");
+
+        compile_circuit(circuit, true).1.unwrap()
+    }
+}
+
+fn compile_circuit<F: PrimeField>(circuit: &Circuit<F>, with_assignment: bool) -> (R1CS<F>, Option<Assignment<F>>) {
+    let num_inputs = circuit.num_inputs();
+    let num_outputs = circuit.layers.first()
+        .map(|layer| layer.gates.iter().map(|gate| gate.output_index).max().unwrap_or(0) + 1)
+        .unwrap_or(num_inputs);
+    let inner_mul_gates = circuit.layers.iter().skip(1)
+        .flat_map(|layer| layer.gates.iter())
+        .filter(|gate| gate.operator == Operator::Mul)
+        .count();
+
+    let mut r1cs = R1CS::new(num_outputs, num_inputs + inner_mul_gates);
+    let mut witness = Vec::new();
+
+    if with_assignment {
+        witness.extend_from_slice(circuit.layer_evaluations.last().unwrap());
+    }
+
+    // linear combination over z for every wire of the current layer, starting from the inputs
+    let mut wires: Vec<LinearCombination<F>> = (0..num_inputs)
+        .map(|i| BTreeMap::from([(r1cs.witness_variable(i), F::one())]))
+        .collect();
+    let mut next_witness = num_inputs;
+
+    for (layer_index, layer) in circuit.layers.iter().enumerate().rev() {
+        let width = layer.gates.iter().map(|gate| gate.output_index).max().unwrap_or(0) + 1;
+        let mut outputs: Vec<LinearCombination<F>> = vec![BTreeMap::new(); width];
+        let mut bound = vec![false; width];
+
+        for gate in layer.gates.iter() {
+            let left = &wires[gate.left_index];
+            let right = &wires[gate.right_index];
+
+            match gate.operator {
+                Operator::Add => {
+                    outputs[gate.output_index] = add_linear_combinations(left, right);
+                },
+                Operator::Mul => {
+                    let variable = if layer_index == 0 {
+                        bound[gate.output_index] = true;
+                        r1cs.public_variable(gate.output_index)
+                    } else {
+                        if with_assignment {
+                            witness.push(circuit.layer_evaluations[layer_index][gate.output_index]);
+                        }
+                        next_witness += 1;
+                        r1cs.witness_variable(next_witness - 1)
+                    };
+
+                    r1cs.add_constraint(to_row(left), to_row(right), vec![(variable, F::one())]);
+                    outputs[gate.output_index] = BTreeMap::from([(variable, F::one())]);
+                }
+            }
+        }
+
+        if layer_index == 0 {
+            for (output_index, combination) in outputs.iter().enumerate() {
+                if !bound[output_index] {
+                    r1cs.add_constraint(to_row(combination), vec![(0, F::one())], vec![(r1cs.public_variable(output_index), F::one())]);
+                }
+            }
+        }
+
+        wires = outputs;
+    }
+
+    let assignment = with_assignment.then(|| (circuit.layer_evaluations[0].clone(), witness));
+
+    (r1cs, assignment)
+}
+
+fn add_linear_combinations<F: PrimeField>(left: &LinearCombination<F>, right: &LinearCombination<F>) -> LinearCombination<F> {
+    let mut result = left.clone();
+
+    for (variable, coefficient) in right.iter() {
+        *result.entry(*variable).or_insert(F::zero()) += coefficient;
+    }
+
+    result.retain(|_, coefficient| !coefficient.is_zero());
+    result
+}
+
+fn to_row<F: PrimeField>(combination: &LinearCombination<F>) -> Vec<(usize, F)> {
+    combination.iter().map(|(variable, coefficient)| (*variable, *coefficient)).collect()
+}
+
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::zk_project::circuit::{Gate, Layer};
+    use ark_bn254::Fq;
+
+    // x^3 + x + 5 = out, with witness (x, x^2, x^3)
+    fn create_cubic_r1cs() -> R1CS<Fq> {
+        let mut r1cs = R1CS::new(1, 3);
+        let (out, x, x_squared, x_cubed) = (r1cs.public_variable(0), r1cs.witness_variable(0), r1cs.witness_variable(1), r1cs.witness_variable(2));
+
+        r1cs.add_constraint(vec![(x, Fq::from(1))], vec![(x, Fq::from(1))], vec![(x_squared, Fq::from(1))]);
+        r1cs.add_constraint(vec![(x_squared, Fq::from(1))], vec![(x, Fq::from(1))], vec![(x_cubed, Fq::from(1))]);
+        r1cs.add_constraint(vec![(x_cubed, Fq::from(1)), (x, Fq::from(1)), (0, Fq::from(5))], vec![(0, Fq::from(1))], vec![(out, Fq::from(1))]);
+
+        r1cs
+    }
+
+    fn create_circuit() -> Circuit<Fq> {
+        let layer0 = Layer::new(vec![Gate::new(0, 1, 0, Operator::Mul), Gate::new(0, 1, 1, Operator::Add)]);
+        let layer1 = Layer::new(vec![Gate::new(0, 1, 0, Operator::Add), Gate::new(2, 3, 1, Operator::Mul)]);
+
+        Circuit::new(vec![layer0, layer1])
+    }
+
+    #[test]
+    fn test_r1cs_is_satisfied() {
+        let r1cs = create_cubic_r1cs();
+
+        assert_eq!(r1cs.num_variables(), 5);
+        assert_eq!(r1cs.num_constraints(), 3);
+        assert!(r1cs.is_satisfied(&[Fq::from(35)], &[Fq::from(3), Fq::from(9), Fq::from(27)]));
+        assert!(!r1cs.is_satisfied(&[Fq::from(36)], &[Fq::from(3), Fq::from(9), Fq::from(27)]));
+        assert!(!r1cs.is_satisfied(&[Fq::from(35)], &[Fq::from(3), Fq::from(9), Fq::from(28)]));
+        assert!(!r1cs.is_satisfied(&[Fq::from(35)], &[Fq::from(3), Fq::from(9)]));
+    }
+
+    #[test]
+    fn test_r1cs_from_circuit() {
+        let mut circuit = create_circuit();
+        circuit.evaluate(vec![Fq::from(2), Fq::from(3), Fq::from(4), Fq::from(5)]);
+
+        let r1cs = R1CS::from_circuit(&circuit);
+        let (public_inputs, witness) = R1CS::circuit_assignment(&circuit);
+
+        // one constraint per Mul gate and one binding the Add output
+        assert_eq!(r1cs.num_constraints(), 3);
+        assert_eq!(r1cs.num_public, 2);
+        // 4 inputs and the inner Mul gate
+        assert_eq!(r1cs.num_witness, 5);
+        assert_eq!(public_inputs, vec![Fq::from(100), Fq::from(25)]);
+        assert_eq!(witness, vec![Fq::from(2), Fq::from(3), Fq::from(4), Fq::from(5), Fq::from(20)]);
+
+        assert!(r1cs.is_satisfied(&public_inputs, &witness));
+    }
+
+    #[test]
+    fn test_r1cs_from_circuit_rejects_wrong_assignment() {
+        let mut circuit = create_circuit();
+        circuit.evaluate(vec![Fq::from(2), Fq::from(3), Fq::from(4), Fq::from(5)]);
+
+        let r1cs = R1CS::from_circuit(&circuit);
+        let (public_inputs, mut witness) = R1CS::circuit_assignment(&circuit);
+
+        assert!(!r1cs.is_satisfied(&[Fq::from(100), Fq::from(26)], &witness));
+
+        witness[4] += Fq::from(1);
+        assert!(!r1cs.is_satisfied(&public_inputs, &witness));
+    }
+
+    #[test]
+    fn test_r1cs_from_deeper_circuit() {
+        let layer0 = Layer::new(vec![Gate::new(0, 1, 0, Operator::Add)]);
+        let layer1 = Layer::new(vec![Gate::new(0, 1, 0, Operator::Add), Gate::new(2, 3, 1, Operator::Mul)]);
+        let layer2 = Layer::new(vec![
+            Gate::new(0, 1, 0, Operator::Add),
+            Gate::new(2, 3, 1, Operator::Mul),
+            Gate::new(4, 5, 2, Operator::Mul),
+            Gate::new(6, 7, 3, Operator::Mul)
+        ]);
+
+        let mut circuit = Circuit::<Fq>::new(vec![layer0, layer1, layer2]);
+        circuit.evaluate((1..=8).map(Fq::from).collect());
+
+        let r1cs = R1CS::from_circuit(&circuit);
+        let (public_inputs, witness) = R1CS::circuit_assignment(&circuit);
+
+        assert_eq!(r1cs.num_constraints(), 4 + 1);
+        assert_eq!(public_inputs, vec![Fq::from(1695)]);
+        assert!(r1cs.is_satisfied(&public_inputs, &witness));
+    }
+}
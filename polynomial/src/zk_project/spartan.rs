@@ -0,0 +1,302 @@
+use ark_ff::PrimeField;
+
+use super::{
+    circuit::{ProductPoly, SumPoly},
+    multilinear_commitment::MultilinearCommitment,
+    multilinear_polynomial::{eq_eval, eq_evaluations, MultilinearPoly},
+    partial_sumcheck::{prove_rounds, verify_rounds},
+    r1cs::{SparseMatrix, R1CS},
+    transcript::{HashTrait, Transcript},
+};
+
+// Spartan-style SNARK for R1CS.
+//
+// z is laid out as (witness, 1, public inputs), padded so both halves have the same power of two
+// length. The first variable of z~ then selects between the committed witness and the public part
+// the verifier can evaluate itself:
+//     z~(y) = (1 - y_0) * w~(y_1, ..) + y_0 * io~(y_1, ..)
+//
+// The outer sumcheck proves sum_x eq(tau, x) * (Az~(x) * Bz~(x) - Cz~(x)) = 0, the inner sumcheck
+// reduces the claims on Az~, Bz~ and Cz~ at r_x to one evaluation of z~ at r_y.
+pub struct Spartan<F: PrimeField, C: MultilinearCommitment<F>> {
+    pub r1cs: R1CS<F>,
+    pub commitment_scheme: C,
+    // variables indexing the (padded) constraints
+    constraint_vars: usize,
+    // variables indexing each half of z
+    half_vars: usize
+}
+
+pub struct SpartanProof<F: PrimeField, C: MultilinearCommitment<F>> {
+    pub witness_commitment: C::Commitment,
+    pub outer_sumcheck: Vec<Vec<F>>,
+    // Az~(r_x), Bz~(r_x), Cz~(r_x)
+    pub az_eval: F,
+    pub bz_eval: F,
+    pub cz_eval: F,
+    pub inner_sumcheck: Vec<Vec<F>>,
+    // w~(r_y[1..])
+    pub witness_eval: F,
+    pub witness_opening: C::Proof
+}
+
+impl <F: PrimeField, C: MultilinearCommitment<F>> Spartan<F, C> {
+    pub fn new(r1cs: R1CS<F>, commitment_scheme: C) -> Self {
+        let constraint_vars = num_vars(r1cs.num_constraints());
+        let half_vars = num_vars(r1cs.num_witness.max(1 + r1cs.num_public));
+
+        Self { r1cs, commitment_scheme, constraint_vars, half_vars }
+    }
+
+    pub fn prove<K: HashTrait>(&self, public_inputs: &[F], witness: &[F], hash_function: K) -> SpartanProof<F, C> {
+        assert!(public_inputs.len() == self.r1cs.num_public, "wrong number of public inputs");
+        assert!(witness.len() == self.r1cs.num_witness, "wrong number of witness elements");
+
+        let mut padded_witness = witness.to_vec();
+        padded_witness.resize(1 << self.half_vars, F::zero());
+        let witness_poly = MultilinearPoly::new(padded_witness.clone());
+
+        let (witness_commitment, prover_data) = self.commitment_scheme.commit(&witness_poly);
+
+        let mut transcript = Transcript::new(b"spartan", hash_function);
+        transcript.append_message(b"witness_commitment", &C::commitment_bytes(&witness_commitment));
+        transcript.append_field_elements(b"public_inputs", public_inputs);
+
+        // outer sumcheck
+        let tau = transcript.challenge_scalars(b"tau", self.constraint_vars);
+
+        let z = [padded_witness, self.public_half(public_inputs)].concat();
+        let az = self.padded_product(&self.r1cs.a, &z);
+        let bz = self.padded_product(&self.r1cs.b, &z);
+        let cz = self.padded_product(&self.r1cs.c, &z);
+        let eq_tau = eq_evaluations(&tau);
+        let negated_cz: Vec<F> = cz.iter().map(|value| -*value).collect();
+
+        let outer_poly = SumPoly::new(vec![
+            ProductPoly::new(vec![eq_tau.clone(), az.clone(), bz.clone()]),
+            ProductPoly::new(vec![eq_tau, negated_cz])
+        ]);
+        let (outer_sumcheck, r_x) = prove_rounds(outer_poly, self.constraint_vars, &mut transcript);
+
+        let az_eval = MultilinearPoly::new(az).evaluate(&r_x);
+        let bz_eval = MultilinearPoly::new(bz).evaluate(&r_x);
+        let cz_eval = MultilinearPoly::new(cz).evaluate(&r_x);
+
+        transcript.append_field_elements(b"matrix_evaluations", &[az_eval, bz_eval, cz_eval]);
+        let combination = transcript.challenge_scalars(b"combination", 3);
+
+        // inner sumcheck
+        let eq_r_x = eq_evaluations(&r_x);
+        let mut combined_matrix = vec![F::zero(); z.len()];
+        for (matrix, weight) in [&self.r1cs.a, &self.r1cs.b, &self.r1cs.c].into_iter().zip(combination.iter()) {
+            for (row_index, row) in matrix.rows.iter().enumerate() {
+                for (col, value) in row.iter() {
+                    combined_matrix[self.column_index(*col)] += *weight * eq_r_x[row_index] * value;
+                }
+            }
+        }
+
+        let inner_poly = SumPoly::new(vec![ProductPoly::new(vec![combined_matrix, z])]);
+        let (inner_sumcheck, r_y) = prove_rounds(inner_poly, self.half_vars + 1, &mut transcript);
+
+        let witness_point = r_y[1..].to_vec();
+        let witness_eval = witness_poly.evaluate(&witness_point);
+
+        transcript.append_field_element(b"witness_evaluation", &witness_eval);
+        let witness_opening = self.commitment_scheme.open(&witness_poly, &prover_data, &witness_point, &mut transcript);
+
+        SpartanProof {
+            witness_commitment,
+            outer_sumcheck,
+            az_eval,
+            bz_eval,
+            cz_eval,
+            inner_sumcheck,
+            witness_eval,
+            witness_opening
+        }
+    }
+
+    pub fn verify<K: HashTrait>(&self, public_inputs: &[F], proof: &SpartanProof<F, C>, hash_function: K) -> bool {
+        if public_inputs.len() != self.r1cs.num_public {
+            return false;
+        }
+
+        let mut transcript = Transcript::new(b"spartan", hash_function);
+        transcript.append_message(b"witness_commitment", &C::commitment_bytes(&proof.witness_commitment));
+        transcript.append_field_elements(b"public_inputs", public_inputs);
+
+        // outer sumcheck
+        let tau = transcript.challenge_scalars(b"tau", self.constraint_vars);
+
+        let Some((outer_claim, r_x)) = verify_rounds(F::zero(), &proof.outer_sumcheck, self.constraint_vars, 3, &mut transcript) else {
+            return false;
+        };
+
+        if outer_claim != eq_eval(&tau, &r_x) * (proof.az_eval * proof.bz_eval - proof.cz_eval) {
+            return false;
+        }
+
+        transcript.append_field_elements(b"matrix_evaluations", &[proof.az_eval, proof.bz_eval, proof.cz_eval]);
+        let combination = transcript.challenge_scalars(b"combination", 3);
+
+        // inner sumcheck
+        let inner_claimed_sum = combination[0] * proof.az_eval + combination[1] * proof.bz_eval + combination[2] * proof.cz_eval;
+
+        let Some((inner_claim, r_y)) = verify_rounds(inner_claimed_sum, &proof.inner_sumcheck, self.half_vars + 1, 2, &mut transcript) else {
+            return false;
+        };
+
+        // the verifier evaluates the matrices itself, in time linear in their number of entries
+        let eq_r_x = eq_evaluations(&r_x);
+        let eq_r_y = eq_evaluations(&r_y);
+        let mut matrix_eval = F::zero();
+        for (matrix, weight) in [&self.r1cs.a, &self.r1cs.b, &self.r1cs.c].into_iter().zip(combination.iter()) {
+            for (row_index, row) in matrix.rows.iter().enumerate() {
+                for (col, value) in row.iter() {
+                    matrix_eval += *weight * eq_r_x[row_index] * eq_r_y[self.column_index(*col)] * value;
+                }
+            }
+        }
+
+        let witness_point = r_y[1..].to_vec();
+        let public_eval = MultilinearPoly::new(self.public_half(public_inputs)).evaluate(&witness_point);
+        let z_eval = (F::one() - r_y[0]) * proof.witness_eval + r_y[0] * public_eval;
+
+        if inner_claim != matrix_eval * z_eval {
+            return false;
+        }
+
+        transcript.append_field_element(b"witness_evaluation", &proof.witness_eval);
+        self.commitment_scheme.verify(&proof.witness_commitment, &witness_point, proof.witness_eval, &proof.witness_opening, &mut transcript)
+    }
+
+    // position of the R1CS variable `col` (from z = (1, public, witness)) in Spartan's z
+    fn column_index(&self, col: usize) -> usize {
+        if col > self.r1cs.num_public {
+            col - 1 - self.r1cs.num_public
+        } else {
+            (1 << self.half_vars) + col
+        }
+    }
+
+    // (1, public inputs) padded to half the length of z
+    fn public_half(&self, public_inputs: &[F]) -> Vec<F> {
+        let mut public_half = vec![F::one()];
+        public_half.extend_from_slice(public_inputs);
+        public_half.resize(1 << self.half_vars, F::zero());
+
+        public_half
+    }
+
+    // M z with the rows padded to the constraint hypercube
+    fn padded_product(&self, matrix: &SparseMatrix<F>, z: &[F]) -> Vec<F> {
+        let mut product: Vec<F> = matrix.rows.iter()
+            .map(|row| row.iter().map(|(col, value)| *value * z[self.column_index(*col)]).sum())
+            .collect();
+        product.resize(1 << self.constraint_vars, F::zero());
+
+        product
+    }
+}
+
+fn num_vars(length: usize) -> usize {
+    length.next_power_of_two().trailing_zeros() as usize
+}
+
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::zk_project::{
+        circuit::{Circuit, Gate, Layer, Operator},
+        ligero::Ligero,
+        multilinear_commitment::TrivialCommitment,
+    };
+    use ark_bn254::Fq;
+    use sha3::{Keccak256, Digest};
+
+    // x^3 + x + 5 = out, with witness (x, x^2, x^3)
+    fn create_cubic_r1cs() -> R1CS<Fq> {
+        let mut r1cs = R1CS::new(1, 3);
+        let (out, x, x_squared, x_cubed) = (r1cs.public_variable(0), r1cs.witness_variable(0), r1cs.witness_variable(1), r1cs.witness_variable(2));
+
+        r1cs.add_constraint(vec![(x, Fq::from(1))], vec![(x, Fq::from(1))], vec![(x_squared, Fq::from(1))]);
+        r1cs.add_constraint(vec![(x_squared, Fq::from(1))], vec![(x, Fq::from(1))], vec![(x_cubed, Fq::from(1))]);
+        r1cs.add_constraint(vec![(x_cubed, Fq::from(1)), (x, Fq::from(1)), (0, Fq::from(5))], vec![(0, Fq::from(1))], vec![(out, Fq::from(1))]);
+
+        r1cs
+    }
+
+    #[test]
+    fn test_spartan_cubic() {
+        let spartan = Spartan::new(create_cubic_r1cs(), TrivialCommitment);
+        let public_inputs = [Fq::from(35)];
+        let witness = [Fq::from(3), Fq::from(9), Fq::from(27)];
+
+        let proof = spartan.prove(&public_inputs, &witness, Keccak256::new());
+
+        assert_eq!(proof.outer_sumcheck.len(), 2);
+        assert_eq!(proof.inner_sumcheck.len(), 3);
+        assert!(spartan.verify(&public_inputs, &proof, Keccak256::new()));
+        assert!(!spartan.verify(&[Fq::from(36)], &proof, Keccak256::new()));
+    }
+
+    #[test]
+    fn test_spartan_rejects_unsatisfying_witness() {
+        let spartan = Spartan::new(create_cubic_r1cs(), TrivialCommitment);
+        let public_inputs = [Fq::from(35)];
+        let witness = [Fq::from(3), Fq::from(9), Fq::from(28)];
+
+        let proof = spartan.prove(&public_inputs, &witness, Keccak256::new());
+
+        assert!(!spartan.verify(&public_inputs, &proof, Keccak256::new()));
+    }
+
+    #[test]
+    fn test_spartan_rejects_tampered_evaluation() {
+        let spartan = Spartan::new(create_cubic_r1cs(), TrivialCommitment);
+        let public_inputs = [Fq::from(35)];
+        let witness = [Fq::from(3), Fq::from(9), Fq::from(27)];
+
+        let mut proof = spartan.prove(&public_inputs, &witness, Keccak256::new());
+        proof.witness_eval += Fq::from(1);
+
+        assert!(!spartan.verify(&public_inputs, &proof, Keccak256::new()));
+    }
+
+    #[test]
+    fn test_spartan_from_circuit() {
+        let layer0 = Layer::new(vec![Gate::new(0, 1, 0, Operator::Add)]);
+        let layer1 = Layer::new(vec![Gate::new(0, 1, 0, Operator::Add), Gate::new(2, 3, 1, Operator::Mul)]);
+        let layer2 = Layer::new(vec![
+            Gate::new(0, 1, 0, Operator::Add),
+            Gate::new(2, 3, 1, Operator::Mul),
+            Gate::new(4, 5, 2, Operator::Mul),
+            Gate::new(6, 7, 3, Operator::Mul)
+        ]);
+
+        let mut circuit = Circuit::<Fq>::new(vec![layer0, layer1, layer2]);
+        circuit.evaluate((1..=8).map(Fq::from).collect());
+
+        let spartan = Spartan::new(R1CS::from_circuit(&circuit), TrivialCommitment);
+        let (public_inputs, witness) = R1CS::circuit_assignment(&circuit);
+
+        let proof = spartan.prove(&public_inputs, &witness, Keccak256::new());
+        assert!(spartan.verify(&public_inputs, &proof, Keccak256::new()));
+    }
+
+    #[test]
+    fn test_spartan_with_ligero() {
+        // the verifier only sees a Merkle root of the witness polynomial
+        let spartan = Spartan::new(create_cubic_r1cs(), Ligero::<Keccak256>::new(4, 16).unwrap());
+        let public_inputs = [Fq::from(35)];
+        let witness = [Fq::from(3), Fq::from(9), Fq::from(27)];
+
+        let mut proof = spartan.prove(&public_inputs, &witness, Keccak256::new());
+        assert!(spartan.verify(&public_inputs, &proof, Keccak256::new()));
+
+        proof.witness_eval += Fq::from(1);
+        assert!(!spartan.verify(&public_inputs, &proof, Keccak256::new()));
+    }
+}
@@ -1,58 +1,60 @@
-use ark_bn254::Fq;
-use ark_ff::{BigInteger, Field, PrimeField};
-use ark_std::rand::Rng;
-use sha2::{Sha256, Digest};
-use super::{multilinear_polynomial::MultilinearPoly, transcript::{HashTrait, Transcript}};
-const PRIME: u64 = 23; 
+use ark_ff::PrimeField;
+use super::{channel::Channel, multilinear_polynomial::MultilinearPoly, partial_sumcheck::SumcheckVerifier};
 type Polynomial<F> = MultilinearPoly<F>;
 
 
 
-fn prover<F : PrimeField, K: HashTrait>(initial_poly: Polynomial<F>, num_vars: usize, hash_function: K) -> (F, Vec<Vec<F>>) {
+// Sumcheck over one multilinear polynomial. Prover and verifier are state machines driven over a
+// channel: a `Transcript` gives the Fiat-Shamir protocol, an `InteractiveChannel` the interactive one.
+pub fn prover<F : PrimeField, C: Channel<F>>(initial_poly: Polynomial<F>, num_vars: usize, channel: &mut C) -> (F, Vec<Vec<F>>) {
 
     let claimed_sum: F = compute_sum(&initial_poly);
 
     let mut proof = vec![];
-    let mut transcript = Transcript::init(hash_function);
 
-    transcript.absorb(&claimed_sum.into_bigint().to_bytes_be());
+    channel.send(b"claimed_sum", &[claimed_sum]);
 
-    transcript.absorb(&to_bytes(&initial_poly.coefficients));
+    channel.send(b"polynomial", &initial_poly.coefficients);
 
-    let mut poly = initial_poly;
-    
-    for round in 0..num_vars {
-        
-        let round_poly = generate_round_poly(&poly);
-        
-        transcript.absorb(&to_bytes(&round_poly));
-        
-        proof.push(round_poly);
+    let mut prover = Prover::new(initial_poly);
+
+    for _ in 0..num_vars {
 
-        let challenge = transcript.squeeze();
-        dbg!(&challenge);
+        let round_poly = prover.round_message();
 
-       poly = poly.partial_evaluate((0, challenge));
+        channel.send(b"round_poly", &round_poly);
+
+        proof.push(round_poly);
 
-       dbg!(&poly.coefficients);
+        prover.receive_challenge(channel.challenge(b"challenge"));
     }
-    
+
     (claimed_sum, proof)
 }
 
+// the prover as a state machine, the verifier side is the polynomial-agnostic `SumcheckVerifier`
+pub struct Prover<F: PrimeField> {
+    poly: Polynomial<F>
+}
+
+impl <F: PrimeField> Prover<F> {
+    pub fn new(poly: Polynomial<F>) -> Self {
+        Self { poly }
+    }
+
+    pub fn round_message(&self) -> Vec<F> {
+        generate_round_poly(&self.poly)
+    }
 
+    pub fn receive_challenge(&mut self, challenge: F) {
+        self.poly = self.poly.partial_evaluate((0, challenge));
+    }
+}
 
 
 
 
- fn to_bytes<F:PrimeField>(values: &Vec<F>) -> Vec<u8>{
-    let mut result = vec![];
-    for value in values{
-       result.extend(value.into_bigint().to_bytes_be());
-    }
 
-    result
- }
 
 
 
@@ -67,40 +69,30 @@ fn prover<F : PrimeField, K: HashTrait>(initial_poly: Polynomial<F>, num_vars: u
  }
 
 
-fn verifier<F : PrimeField, K: HashTrait>(claimed_sum: F, proof: &Vec<Vec<F>>, num_vars: usize , initial_poly: &Polynomial<F>, hash_function: K) -> bool {
-    
-    dbg!("Verifier");
+pub fn verifier<F : PrimeField, C: Channel<F>>(claimed_sum: F, proof: &[Vec<F>], num_vars: usize , initial_poly: &Polynomial<F>, channel: &mut C) -> bool {
+
+    if proof.len() != num_vars {
+        return false;
+    }
 
-    let mut transcript = Transcript:: init(hash_function);
-    transcript.absorb(&claimed_sum.into_bigint().to_bytes_be());
-    transcript.absorb(&to_bytes(&initial_poly.coefficients));
+    channel.send(b"claimed_sum", &[claimed_sum]);
+    channel.send(b"polynomial", &initial_poly.coefficients);
 
-    let mut running_claim= claimed_sum;
-    let mut challenges =vec![];
+    let mut verifier = SumcheckVerifier::new(claimed_sum, num_vars, 1);
 
     for round_poly in proof {
-        if round_poly.len() !=2 {
+        if !verifier.receive_round(round_poly) {
            return false;
-        } 
-        assert!(running_claim == round_poly.iter().sum());
-        transcript.absorb(&to_bytes(round_poly));
-
-        let challenge = transcript.squeeze();
-        challenges.push(challenge);
+        }
+        channel.send(b"round_poly", round_poly);
 
-        dbg!(&challenge);
-
-         running_claim = round_poly[0] + challenge * (round_poly[1] - round_poly[0]);
-
-         dbg!(&running_claim);
-        
+        verifier.receive_challenge(channel.challenge(b"challenge"));
     }
-    let final_eval = initial_poly.evaluate(&challenges);
-
-    dbg!(&final_eval);
-    dbg!(&running_claim);
 
-    running_claim == final_eval
+    let Some((running_claim, challenges)) = verifier.final_claim() else {
+        return false;
+    };
+    running_claim == initial_poly.evaluate(&challenges)
 }
 
 
@@ -109,6 +101,8 @@ fn verifier<F : PrimeField, K: HashTrait>(claimed_sum: F, proof: &Vec<Vec<F>>, n
 #[cfg(test)]
 mod test {
 use crate::zk_project::sumcheck_implementation::{prover, verifier};
+use crate::zk_project::{channel::InteractiveChannel, circuit::{ProductPoly, SumPoly}, partial_sumcheck::run_interactive, transcript::Transcript, transcript_log::first_divergence};
+use rand::{rngs::StdRng, SeedableRng};
 
 // Test the Sumcheck protocol
 use super::MultilinearPoly;
@@ -138,23 +132,49 @@ fn test_sumcheck_protocol() {
     let hash_function = Keccak256::new();
 
     // Run the prover
-    let (claimed_sum, proof) = prover(poly.clone(), num_vars, hash_function.clone());
-
-    dbg!(&proof);
-    dbg!(&claimed_sum);
+    let (claimed_sum, proof) = prover(poly.clone(), num_vars, &mut Transcript::new(b"sumcheck", hash_function.clone()));
 
     // Verify the proof
-    let is_valid = verifier(claimed_sum, &proof, num_vars, &poly, hash_function);
+    let is_valid = verifier(claimed_sum, &proof, num_vars, &poly, &mut Transcript::new(b"sumcheck", hash_function));
 
     // Check that the proof is valid
     assert!(is_valid, "Sumcheck protocol verification failed");
-
-    // Print the results
-    println!("Claimed sum: {}", claimed_sum);
-    println!("Proof: {:?}", proof);
-    println!("Verification result: {}", is_valid);
 }
 
+#[test]
+fn test_sumcheck_transcripts_agree() {
+    let poly = create_test_polynomial();
+
+    let mut prover_transcript = Transcript::new_recording(b"sumcheck", Keccak256::new());
+    let (claimed_sum, proof) = prover(poly.clone(), 2, &mut prover_transcript);
+
+    let mut verifier_transcript = Transcript::new_recording(b"sumcheck", Keccak256::new());
+    assert!(verifier(claimed_sum, &proof, 2, &poly, &mut verifier_transcript));
+    assert_eq!(first_divergence(prover_transcript.log().unwrap(), verifier_transcript.log().unwrap()), None);
+
+    // checking the proof against another polynomial diverges at the polynomial message, before any challenge
+    let other_poly = MultilinearPoly::new(vec![Fq::from(1), Fq::from(2), Fq::from(2), Fq::from(5)]);
+    let mut verifier_transcript = Transcript::new_recording(b"sumcheck", Keccak256::new());
+    assert!(!verifier(claimed_sum, &proof, 2, &other_poly, &mut verifier_transcript));
+
+    let divergence = first_divergence(prover_transcript.log().unwrap(), verifier_transcript.log().unwrap()).unwrap();
+    assert_eq!(divergence.index, 2);
+    assert!(divergence.to_string().starts_with("transcripts diverge at operation 2\n  prover:   append \"polynomial\""));
 }
 
+#[test]
+fn test_sumcheck_interactive() {
+    let poly = create_test_polynomial();
+
+    // the drivers run over any channel: with the same seed both sides draw the same verifier randomness
+    let (claimed_sum, proof) = prover(poly.clone(), 2, &mut InteractiveChannel::new(StdRng::seed_from_u64(0)));
+    assert!(verifier(claimed_sum, &proof, 2, &poly, &mut InteractiveChannel::new(StdRng::seed_from_u64(0))));
+    assert!(!verifier(claimed_sum, &proof, 2, &poly, &mut InteractiveChannel::new(StdRng::seed_from_u64(1))));
+
+    // prover and verifier against each other on one channel, through the shared driver
+    let sum_poly = SumPoly::new(vec![ProductPoly::new(vec![poly.coefficients.clone()])]);
+    assert!(run_interactive(Fq::from(10), sum_poly.clone(), 2, &mut InteractiveChannel::new(StdRng::seed_from_u64(0))));
+    assert!(!run_interactive(Fq::from(11), sum_poly, 2, &mut InteractiveChannel::new(StdRng::seed_from_u64(0))));
+}
 
+}
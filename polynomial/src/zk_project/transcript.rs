@@ -1,30 +1,172 @@
 use std::marker::PhantomData;
 use sha3::{Keccak256, Digest};
 
-use ark_ff::PrimeField;
+use ark_ec::AffineRepr;
+use ark_ff::{BigInteger, Field, PrimeField, Zero};
+
+use super::transcript_log::TranscriptEvent;
 
 pub struct Transcript <K: HashTrait, F: PrimeField> {
     _field: PhantomData<F>,
-    hash_function: K
+    hash_function: K,
+    log: Option<Vec<TranscriptEvent>>
 }
 
 impl <K: HashTrait, F: PrimeField> Transcript<K, F> {
     pub fn init(hash_function: K) -> Self {
-        Self {_field: PhantomData, hash_function}
+        Self {_field: PhantomData, hash_function, log: None}
+    }
+
+    // Merlin-style transcript bound to a protocol: the domain separator is the first labeled message.
+    pub fn new(domain_separator: &[u8], hash_function: K) -> Self {
+        let mut transcript = Self::init(hash_function);
+        transcript.append_message(b"dom-sep", domain_separator);
+
+        transcript
+    }
+
+    // Same as `new`, but every operation is also logged so that a prover and a verifier
+    // transcript can be compared with `transcript_log::first_divergence`.
+    pub fn new_recording(domain_separator: &[u8], hash_function: K) -> Self {
+        let mut transcript = Self::init(hash_function);
+        transcript.log = Some(Vec::new());
+        transcript.append_message(b"dom-sep", domain_separator);
+
+        transcript
+    }
+
+    pub fn log(&self) -> Option<&[TranscriptEvent]> {
+        self.log.as_deref()
     }
 
+    fn record(&mut self, event: impl FnOnce() -> TranscriptEvent) {
+        if let Some(log) = self.log.as_mut() {
+            log.push(event());
+        }
+    }
+
+    // Appends `label || message` with both lengths framed in, so no two sequences of labeled
+    // messages hash the same bytes. The EVM layout packs them with no lengths, like `abi.encodePacked`.
+    pub fn append_message(&mut self, label: &[u8], message: &[u8]) {
+        self.record(|| TranscriptEvent::Append { label: label.to_vec(), message: message.to_vec() });
+
+        if K::LAYOUT == Layout::Framed {
+            self.hash_function.append(&(label.len() as u32).to_be_bytes());
+            self.hash_function.append(label);
+            self.hash_function.append(&(message.len() as u32).to_be_bytes());
+        } else {
+            self.hash_function.append(label);
+        }
+        self.hash_function.append(message);
+    }
+
+    // field elements are encoded as fixed width big-endian integers
+    pub fn append_field_element(&mut self, label: &[u8], element: &F) {
+        self.append_message(label, &Self::field_bytes(element));
+    }
+
+    pub fn append_field_elements(&mut self, label: &[u8], elements: &[F]) {
+        let message: Vec<u8> = elements.iter().flat_map(Self::field_bytes).collect();
+
+        self.append_message(label, &message);
+    }
+
+    // Curve points are encoded with their compressed canonical serialization, or in the EVM layout as
+    // the words of x then y, with the identity written as zeros as the precompiles expect.
+    pub fn append_point<P: AffineRepr>(&mut self, label: &[u8], point: &P) {
+        let mut message = Vec::new();
+
+        match K::LAYOUT {
+            Layout::Framed => point.serialize_compressed(&mut message).expect("serializing into a vector cannot fail"),
+            Layout::EvmPacked => {
+                let (x, y) = point.xy().unwrap_or((P::BaseField::zero(), P::BaseField::zero()));
+                for coordinate in x.to_base_prime_field_elements().chain(y.to_base_prime_field_elements()) {
+                    message.extend(evm_word(coordinate.into_bigint().to_bytes_be()));
+                }
+            }
+        }
+
+        self.append_message(label, &message);
+    }
+
+    fn field_bytes(element: &F) -> Vec<u8> {
+        let bytes = element.into_bigint().to_bytes_be();
+
+        match K::LAYOUT {
+            Layout::Framed => bytes,
+            Layout::EvmPacked => evm_word(bytes)
+        }
+    }
+
+    pub fn challenge_scalar(&mut self, label: &[u8]) -> F {
+        self.append_message(label, &[]);
+        self.squeeze()
+    }
+
+    pub fn challenge_scalars(&mut self, label: &[u8], n: usize) -> Vec<F> {
+        self.append_message(label, &(n as u32).to_be_bytes());
+        self.squeeze_n(n)
+    }
+
+    // raw bytes with no label or framing, prefer the labeled methods above
     pub fn absorb(&mut self, data: &[u8]) {
+        self.record(|| TranscriptEvent::Absorb(data.to_vec()));
         self.hash_function.append(data);
     }
 
-    pub fn squeeze(&self) -> F {
-        let hash_output = self.hash_function.generate_hash();
-        F::from_be_bytes_mod_order(&hash_output)
+    // Every squeeze feeds its hash output back into the state, so consecutive squeezes differ.
+    // The challenge is reduced from at least 128 bits more than the modulus, which keeps
+    // it statistically close to uniform (a single 32 byte digest is biased for BN254).
+    // The EVM layout reduces a single digest instead, which is what `uint256(h) % r` computes on chain.
+    pub fn squeeze(&mut self) -> F {
+        let required_bytes = match K::LAYOUT {
+            Layout::Framed => (F::MODULUS_BIT_SIZE as usize).div_ceil(8) + 16,
+            Layout::EvmPacked => EVM_WORD_SIZE
+        };
+        let mut wide_output = Vec::with_capacity(required_bytes);
+
+        while wide_output.len() < required_bytes {
+            let hash_output = self.hash_function.generate_hash();
+            self.hash_function.append(&hash_output);
+            wide_output.extend(hash_output);
+        }
+
+        let challenge = F::from_be_bytes_mod_order(&wide_output[..required_bytes]);
+        self.record(|| TranscriptEvent::Squeeze(challenge.into_bigint().to_bytes_be()));
+
+        challenge
     }
+
+    pub fn squeeze_n(&mut self, n: usize) -> Vec<F> {
+        (0..n).map(|_| self.squeeze()).collect()
+    }
+}
+
+
+// How a transcript lays out its messages in the hash input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Layout {
+    // length framed labels and messages, wide reduction of challenges
+    Framed,
+    // `abi.encodePacked` of 32 byte big-endian words, one digest per challenge
+    EvmPacked
+}
+
+const EVM_WORD_SIZE: usize = 32;
+
+fn evm_word(bytes: Vec<u8>) -> Vec<u8> {
+    assert!(bytes.len() <= EVM_WORD_SIZE || bytes[..bytes.len() - EVM_WORD_SIZE].iter().all(|byte| *byte == 0), "value does not fit in a 32 byte word");
+
+    let mut word = vec![0; EVM_WORD_SIZE.saturating_sub(bytes.len())];
+    word.extend_from_slice(&bytes[bytes.len().saturating_sub(EVM_WORD_SIZE)..]);
+
+    word
 }
 
 
 pub trait HashTrait {
+  const LAYOUT: Layout = Layout::Framed;
+
   fn append(&mut self, data: &[u8]);
   fn generate_hash(&self) -> Vec<u8>;
 }
@@ -40,13 +182,74 @@ impl HashTrait for Keccak256 {
     }
 }
 
+// Keccak256 with the byte layout a Solidity verifier reproduces: the transcript is the
+// `abi.encodePacked` concatenation of every label and word, each challenge is
+// `uint256(keccak256(transcript)) % r` and its digest is appended to the transcript.
+#[derive(Clone, Default)]
+pub struct EvmKeccak256(Keccak256);
+
+impl EvmKeccak256 {
+    pub fn new() -> Self {
+        Self(Keccak256::new())
+    }
+}
+
+impl HashTrait for EvmKeccak256 {
+    const LAYOUT: Layout = Layout::EvmPacked;
+
+    fn append(&mut self, data: &[u8]) {
+        self.0.update(data)
+    }
+
+    fn generate_hash(&self) -> Vec<u8> {
+        self.0.clone().finalize().to_vec()
+    }
+}
+
+#[cfg(feature = "sha256")]
+impl HashTrait for sha2::Sha256 {
+    fn append(&mut self, data: &[u8]) {
+        sha2::Digest::update(self, data)
+    }
+
+    fn generate_hash(&self) -> Vec<u8> {
+        sha2::Digest::finalize(self.clone()).to_vec()
+    }
+}
+
+#[cfg(feature = "blake2")]
+impl HashTrait for blake2::Blake2s256 {
+    fn append(&mut self, data: &[u8]) {
+        blake2::Digest::update(self, data)
+    }
+
+    fn generate_hash(&self) -> Vec<u8> {
+        blake2::Digest::finalize(self.clone()).to_vec()
+    }
+}
+
+#[cfg(feature = "blake3")]
+impl HashTrait for blake3::Hasher {
+    fn append(&mut self, data: &[u8]) {
+        self.update(data);
+    }
+
+    fn generate_hash(&self) -> Vec<u8> {
+        self.finalize().as_bytes().to_vec()
+    }
+}
+
+
+
 
 
 #[cfg(test)]
 mod test {
 
-    use super::Transcript;
-    use ark_bn254::Fq;
+    use super::{EvmKeccak256, HashTrait, Transcript};
+    use crate::zk_project::{circuit::{ProductPoly, SumPoly}, partial_sumcheck::{prove_rounds, verify_rounds}};
+    use ark_bn254::{Fq, G1Affine};
+    use ark_ec::AffineRepr;
     use ark_ff::{BigInteger, PrimeField};
     use super::Keccak256;
     use sha3::Digest;
@@ -63,8 +266,147 @@ mod test {
         let challenge = transcript.squeeze();
         let challenge1 = transcript.squeeze();
 
-        dbg!(challenge);
-        dbg!(challenge1);
+        assert_ne!(challenge, challenge1);
+    }
+
+    #[test]
+    fn test_squeeze_is_deterministic() {
+        let mut transcript = Transcript::<Keccak256, Fq>::init(Keccak256::new());
+        let mut transcript1 = Transcript::<Keccak256, Fq>::init(Keccak256::new());
+
+        transcript.absorb("girl".as_bytes());
+        transcript1.absorb("girl".as_bytes());
+
+        assert_eq!(transcript.squeeze_n(3), transcript1.squeeze_n(3));
+
+        transcript.absorb(&[1]);
+        transcript1.absorb(&[2]);
+
+        assert_ne!(transcript.squeeze(), transcript1.squeeze());
+    }
+
+    #[test]
+    fn test_labeled_messages_are_framed() {
+        let mut transcript = Transcript::<Keccak256, Fq>::new(b"test", Keccak256::new());
+        let mut transcript1 = Transcript::<Keccak256, Fq>::new(b"test", Keccak256::new());
+
+        transcript.append_message(b"a", b"bc");
+        transcript1.append_message(b"ab", b"c");
+
+        assert_ne!(transcript.challenge_scalar(b"r"), transcript1.challenge_scalar(b"r"));
+    }
+
+    #[test]
+    fn test_domain_separation() {
+        let mut transcript = Transcript::<Keccak256, Fq>::new(b"protocol-a", Keccak256::new());
+        let mut transcript1 = Transcript::<Keccak256, Fq>::new(b"protocol-b", Keccak256::new());
+
+        transcript.append_field_element(b"x", &Fq::from(7));
+        transcript1.append_field_element(b"x", &Fq::from(7));
+
+        assert_ne!(transcript.challenge_scalar(b"r"), transcript1.challenge_scalar(b"r"));
+    }
+
+    #[test]
+    fn test_typed_appends() {
+        let mut transcript = Transcript::<Keccak256, Fq>::new(b"test", Keccak256::new());
+        let mut transcript1 = Transcript::<Keccak256, Fq>::new(b"test", Keccak256::new());
+
+        transcript.append_field_elements(b"xs", &[Fq::from(1), Fq::from(2)]);
+        transcript1.append_message(b"xs", &[Fq::from(1).into_bigint().to_bytes_be(), Fq::from(2).into_bigint().to_bytes_be()].concat());
+
+        let point = G1Affine::generator();
+        transcript.append_point(b"g", &point);
+        transcript1.append_point(b"g", &point);
+
+        assert_eq!(transcript.challenge_scalars(b"r", 2), transcript1.challenge_scalars(b"r", 2));
+    }
+
+    #[test]
+    fn test_squeeze_n() {
+        let mut transcript = Transcript::<Keccak256, Fq>::init(Keccak256::new());
+        transcript.absorb("girl".as_bytes());
+
+        let challenges = transcript.squeeze_n(4);
+
+        assert_eq!(challenges.len(), 4);
+        for i in 0..challenges.len() {
+            for j in i + 1..challenges.len() {
+                assert_ne!(challenges[i], challenges[j]);
+            }
+        }
+    }
+
+    #[test]
+    fn test_evm_layout() {
+        let mut transcript = Transcript::<EvmKeccak256, Fq>::new(b"test", EvmKeccak256::new());
+        transcript.append_field_element(b"x", &Fq::from(7));
+        transcript.append_point(b"g", &G1Affine::generator());
+
+        let challenge = transcript.challenge_scalar(b"r");
+
+        // keccak256(abi.encodePacked("dom-sep", "test", "x", uint256(7), "g", uint256(1), uint256(2), "r"))
+        let word = |value: u8| [vec![0; 31], vec![value]].concat();
+        let packed = [b"dom-septestx".to_vec(), word(7), b"g".to_vec(), word(1), word(2), b"r".to_vec()].concat();
+        let digest = Keccak256::digest(&packed);
+
+        assert_eq!(challenge, Fq::from_be_bytes_mod_order(&digest));
+
+        // the digest is appended before the next challenge
+        let next_digest = Keccak256::digest([packed, digest.to_vec(), b"s".to_vec()].concat());
+        assert_eq!(transcript.challenge_scalar(b"s"), Fq::from_be_bytes_mod_order(&next_digest));
+    }
+
+    fn challenges_with<K: HashTrait>(hash_function: K) -> Vec<Fq> {
+        let mut transcript = Transcript::<K, Fq>::new(b"test", hash_function);
+        transcript.append_field_elements(b"xs", &[Fq::from(3), Fq::from(5)]);
+        transcript.append_point(b"g", &G1Affine::generator());
+
+        transcript.challenge_scalars(b"r", 3)
+    }
+
+    // a full sumcheck over the backend: prover and verifier transcripts must derive the same challenges
+    fn round_trip_with<K: HashTrait>(new_hash: impl Fn() -> K) -> bool {
+        let a = vec![Fq::from(0), Fq::from(3), Fq::from(2), Fq::from(5)];
+        let b = vec![Fq::from(1), Fq::from(4), Fq::from(6), Fq::from(2)];
+        let poly = SumPoly::new(vec![ProductPoly::new(vec![a, b])]);
+        let claimed_sum: Fq = poly.reduce().iter().sum();
+
+        let (proof, _) = prove_rounds(poly.clone(), 2, &mut Transcript::new(b"test", new_hash()));
+
+        if verify_rounds(claimed_sum + Fq::from(1), &proof, 2, 2, &mut Transcript::new(b"test", new_hash())).is_some() {
+            return false;
+        }
+        match verify_rounds(claimed_sum, &proof, 2, 2, &mut Transcript::new(b"test", new_hash())) {
+            Some((final_claim, challenges)) => final_claim == poly.evaluate(challenges),
+            None => false
+        }
+    }
+
+    #[test]
+    fn test_backends() {
+        assert!(round_trip_with(Keccak256::new));
+        assert!(round_trip_with(EvmKeccak256::new));
+        #[cfg(feature = "sha256")]
+        assert!(round_trip_with(sha2::Sha256::new));
+        #[cfg(feature = "blake2")]
+        assert!(round_trip_with(blake2::Blake2s256::new));
+        #[cfg(feature = "blake3")]
+        assert!(round_trip_with(blake3::Hasher::new));
+
+        let mut all_challenges = vec![challenges_with(Keccak256::new()), challenges_with(EvmKeccak256::new())];
+        #[cfg(feature = "sha256")]
+        all_challenges.push(challenges_with(sha2::Sha256::new()));
+        #[cfg(feature = "blake2")]
+        all_challenges.push(challenges_with(blake2::Blake2s256::new()));
+        #[cfg(feature = "blake3")]
+        all_challenges.push(challenges_with(blake3::Hasher::new()));
+
+        for (i, challenges) in all_challenges.iter().enumerate() {
+            for other in all_challenges.iter().skip(i + 1) {
+                assert_ne!(challenges, other);
+            }
+        }
     }
 }
 
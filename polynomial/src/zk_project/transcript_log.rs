@@ -0,0 +1,73 @@
+use std::fmt;
+
+// One operation on a recording transcript.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TranscriptEvent {
+    // a labeled message, field elements and points included
+    Append { label: Vec<u8>, message: Vec<u8> },
+    // raw bytes absorbed with no label
+    Absorb(Vec<u8>),
+    // a challenge, as big-endian bytes
+    Squeeze(Vec<u8>)
+}
+
+impl fmt::Display for TranscriptEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TranscriptEvent::Append { label, message } => write!(f, "append {:?} ({} bytes) {}", String::from_utf8_lossy(label), message.len(), hex(message)),
+            TranscriptEvent::Absorb(data) => write!(f, "absorb ({} bytes) {}", data.len(), hex(data)),
+            TranscriptEvent::Squeeze(challenge) => write!(f, "squeeze {}", hex(challenge))
+        }
+    }
+}
+
+// First operation at which two recordings differ, None on either side when that recording ended early.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Divergence {
+    pub index: usize,
+    pub prover: Option<TranscriptEvent>,
+    pub verifier: Option<TranscriptEvent>
+}
+
+impl fmt::Display for Divergence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let describe = |event: &Option<TranscriptEvent>| event.as_ref().map_or("<end of transcript>".to_string(), |event| event.to_string());
+
+        write!(f, "transcripts diverge at operation {}\n  prover:   {}\n  verifier: {}", self.index, describe(&self.prover), describe(&self.verifier))
+    }
+}
+
+pub fn first_divergence(prover: &[TranscriptEvent], verifier: &[TranscriptEvent]) -> Option<Divergence> {
+    (0..prover.len().max(verifier.len()))
+        .find(|i| prover.get(*i) != verifier.get(*i))
+        .map(|index| Divergence { index, prover: prover.get(index).cloned(), verifier: verifier.get(index).cloned() })
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_first_divergence() {
+        let prover = vec![
+            TranscriptEvent::Append { label: b"x".to_vec(), message: vec![1] },
+            TranscriptEvent::Squeeze(vec![7])
+        ];
+        let mut verifier = prover.clone();
+
+        assert_eq!(first_divergence(&prover, &verifier), None);
+
+        verifier[1] = TranscriptEvent::Squeeze(vec![8]);
+        let divergence = first_divergence(&prover, &verifier).unwrap();
+        assert_eq!(divergence.index, 1);
+        assert_eq!(divergence.to_string(), "transcripts diverge at operation 1\n  prover:   squeeze 07\n  verifier: squeeze 08");
+
+        let divergence = first_divergence(&prover, &verifier[..1]).unwrap();
+        assert_eq!(divergence.verifier, None);
+    }
+}
@@ -1 +1,2 @@
-pub mod univariate;
\ No newline at end of file
+pub mod univariate;
+pub mod ntt;
@@ -0,0 +1,232 @@
+use ark_ff::PrimeField;
+
+use crate::univariate::UnivariateDense;
+
+// Multiplicative subgroup of size 2^k generated by a primitive root of unity,
+// with the number theoretic transform between coefficients and evaluations over it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NttDomain<F: PrimeField> {
+    pub size: usize,
+    pub log_size: usize,
+    pub generator: F,
+    pub generator_inverse: F,
+    pub size_inverse: F
+}
+
+impl<F: PrimeField> NttDomain<F> {
+    // Smallest domain holding at least `size` points, None if the field has no such subgroup.
+    pub fn new(size: usize) -> Option<Self> {
+        let size = size.max(1).next_power_of_two();
+        let generator = F::get_root_of_unity(size as u64)?;
+
+        Some(Self {
+            size,
+            log_size: size.trailing_zeros() as usize,
+            generator,
+            generator_inverse: generator.inverse().unwrap(),
+            size_inverse: F::from(size as u64).inverse().unwrap()
+        })
+    }
+
+    pub fn element(&self, index: usize) -> F {
+        self.generator.pow([index as u64])
+    }
+
+    pub fn elements(&self) -> Vec<F> {
+        let mut elements = Vec::with_capacity(self.size);
+        let mut current = F::one();
+
+        for _ in 0..self.size {
+            elements.push(current);
+            current *= self.generator;
+        }
+
+        elements
+    }
+
+    // evaluations of the polynomial with the given coefficients at every element of the domain
+    pub fn fft(&self, coefficients: &[F]) -> Vec<F> {
+        assert!(coefficients.len() <= self.size, "too many coefficients for this domain");
+
+        let mut values = coefficients.to_vec();
+        values.resize(self.size, F::zero());
+        transform(&mut values, self.generator);
+
+        values
+    }
+
+    // coefficients of the polynomial taking the given values over the domain
+    pub fn ifft(&self, evaluations: &[F]) -> Vec<F> {
+        assert!(evaluations.len() <= self.size, "too many evaluations for this domain");
+
+        let mut values = evaluations.to_vec();
+        values.resize(self.size, F::zero());
+        transform(&mut values, self.generator_inverse);
+
+        values.iter().map(|value| *value * self.size_inverse).collect()
+    }
+
+    // evaluations over the coset offset * <generator>
+    pub fn coset_fft(&self, coefficients: &[F], offset: F) -> Vec<F> {
+        let mut power = F::one();
+        let shifted: Vec<F> = coefficients.iter().map(|coefficient| {
+            let value = *coefficient * power;
+            power *= offset;
+            value
+        }).collect();
+
+        self.fft(&shifted)
+    }
+
+    pub fn interpolate(&self, evaluations: &[F]) -> UnivariateDense<F> {
+        UnivariateDense::new(self.ifft(evaluations))
+    }
+
+    // Z(X) = X^n - 1
+    pub fn vanishing_polynomial(&self) -> UnivariateDense<F> {
+        let mut coefficients = vec![F::zero(); self.size + 1];
+        coefficients[0] = -F::one();
+        coefficients[self.size] = F::one();
+
+        UnivariateDense::new(coefficients)
+    }
+
+    pub fn evaluate_vanishing_polynomial(&self, x: F) -> F {
+        x.pow([self.size as u64]) - F::one()
+    }
+
+    // L_i(x) for every element of the domain: L_i(x) = (x^n - 1) * w^i / (n * (x - w^i))
+    pub fn lagrange_coefficients(&self, x: F) -> Vec<F> {
+        let elements = self.elements();
+
+        if let Some(index) = elements.iter().position(|element| *element == x) {
+            let mut coefficients = vec![F::zero(); self.size];
+            coefficients[index] = F::one();
+            return coefficients;
+        }
+
+        let vanishing = self.evaluate_vanishing_polynomial(x) * self.size_inverse;
+        elements.iter().map(|element| vanishing * element * (x - element).inverse().unwrap()).collect()
+    }
+
+    // product of two polynomials through evaluations over a large enough domain
+    pub fn multiply(a: &UnivariateDense<F>, b: &UnivariateDense<F>) -> UnivariateDense<F> {
+        if a.is_zero() || b.is_zero() {
+            return UnivariateDense::zero();
+        }
+
+        let domain = Self::new(a.coefficients.len() + b.coefficients.len() - 1).expect("field has no domain large enough");
+        let a_values = domain.fft(&a.coefficients);
+        let b_values = domain.fft(&b.coefficients);
+        let product: Vec<F> = a_values.iter().zip(b_values.iter()).map(|(x, y)| *x * y).collect();
+
+        domain.interpolate(&product)
+    }
+}
+
+// in place radix-2 Cooley-Tukey transform with `root` a primitive root of unity of order values.len()
+fn transform<F: PrimeField>(values: &mut [F], root: F) {
+    let n = values.len();
+    // a single value is its own transform, and would make the bit reversal shift by usize::BITS
+    if n <= 1 {
+        return;
+    }
+    let log_n = n.trailing_zeros();
+
+    for i in 0..n {
+        let j = i.reverse_bits() >> (usize::BITS - log_n);
+        if i < j {
+            values.swap(i, j);
+        }
+    }
+
+    let mut half = 1;
+    while half < n {
+        let step = root.pow([(n / (2 * half)) as u64]);
+
+        for start in (0..n).step_by(2 * half) {
+            let mut twiddle = F::one();
+            for k in 0..half {
+                let even = values[start + k];
+                let odd = values[start + k + half] * twiddle;
+                values[start + k] = even + odd;
+                values[start + k + half] = even - odd;
+                twiddle *= step;
+            }
+        }
+
+        half *= 2;
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_bn254::Fr;
+
+    #[test]
+    fn test_fft_matches_evaluation() {
+        let domain = NttDomain::<Fr>::new(8).unwrap();
+        let poly = UnivariateDense::new((1..=6).map(Fr::from).collect());
+
+        let evaluations = domain.fft(&poly.coefficients);
+        let expected: Vec<Fr> = domain.elements().iter().map(|x| poly.evaluate(*x)).collect();
+
+        assert_eq!(evaluations, expected);
+        assert_eq!(domain.interpolate(&evaluations), poly);
+    }
+
+    #[test]
+    fn test_domain_size_rounds_up() {
+        let domain = NttDomain::<Fr>::new(5).unwrap();
+
+        assert_eq!(domain.size, 8);
+        assert_eq!(domain.log_size, 3);
+        assert_eq!(domain.element(8), Fr::from(1));
+        assert!(domain.elements().iter().all(|x| domain.evaluate_vanishing_polynomial(*x) == Fr::from(0)));
+    }
+
+    #[test]
+    fn test_coset_fft() {
+        let domain = NttDomain::<Fr>::new(4).unwrap();
+        let poly = UnivariateDense::new(vec![Fr::from(3), Fr::from(1), Fr::from(4)]);
+        let offset = Fr::from(7);
+
+        let evaluations = domain.coset_fft(&poly.coefficients, offset);
+        let expected: Vec<Fr> = domain.elements().iter().map(|x| poly.evaluate(offset * x)).collect();
+
+        assert_eq!(evaluations, expected);
+    }
+
+    #[test]
+    fn test_lagrange_coefficients() {
+        let domain = NttDomain::<Fr>::new(4).unwrap();
+        let values = vec![Fr::from(5), Fr::from(9), Fr::from(2), Fr::from(6)];
+        let x = Fr::from(11);
+
+        let interpolated: Fr = domain.lagrange_coefficients(x).iter().zip(values.iter()).map(|(l, y)| *l * y).sum();
+
+        assert_eq!(interpolated, domain.interpolate(&values).evaluate(x));
+    }
+
+    #[test]
+    fn test_multiply() {
+        let a = UnivariateDense::new((1..=5).map(Fr::from).collect());
+        let b = UnivariateDense::new((3..=9).map(Fr::from).collect());
+
+        assert_eq!(NttDomain::multiply(&a, &b), &a * &b);
+    }
+
+    #[test]
+    fn test_size_one_domain() {
+        let domain = NttDomain::<Fr>::new(1).unwrap();
+
+        assert_eq!(domain.fft(&[Fr::from(7)]), vec![Fr::from(7)]);
+        assert_eq!(domain.ifft(&[Fr::from(7)]), vec![Fr::from(7)]);
+
+        let a = UnivariateDense::new(vec![Fr::from(3)]);
+        let b = UnivariateDense::new(vec![Fr::from(4)]);
+        assert_eq!(NttDomain::multiply(&a, &b), UnivariateDense::new(vec![Fr::from(12)]));
+    }
+}
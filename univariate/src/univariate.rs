@@ -1,9 +1,222 @@
+use std::ops::{Add, Mul, Neg, Sub};
 
-struct UnivariateDense{
-    coefficients : Vec<F>;
+use ark_ff::PrimeField;
+
+// Dense univariate polynomial, coefficients in ascending order of degree.
+// Trailing zero coefficients are trimmed, so the zero polynomial has no coefficients.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnivariateDense<F: PrimeField> {
+    pub coefficients: Vec<F>
+}
+
+impl<F: PrimeField> UnivariateDense<F> {
+    pub fn new(coefficients: Vec<F>) -> Self {
+        let mut poly = Self { coefficients };
+        poly.trim();
+
+        poly
+    }
+
+    pub fn zero() -> Self {
+        Self { coefficients: Vec::new() }
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.coefficients.is_empty()
+    }
+
+    // degree of the zero polynomial is taken to be 0
+    pub fn degree(&self) -> usize {
+        self.coefficients.len().saturating_sub(1)
+    }
+
+    pub fn evaluate(&self, x: F) -> F {
+        self.coefficients.iter().rev().fold(F::zero(), |acc, coefficient| acc * x + coefficient)
+    }
+
+    pub fn scalar_mul(&self, scalar: F) -> Self {
+        Self::new(self.coefficients.iter().map(|coefficient| *coefficient * scalar).collect())
+    }
+
+    // Lagrange interpolation through the given (x, y) points
+    pub fn interpolate(points: &[(F, F)]) -> Self {
+        let mut result = Self::zero();
+
+        for (i, (x_i, y_i)) in points.iter().enumerate() {
+            let mut basis = Self::new(vec![F::one()]);
+            let mut denominator = F::one();
+
+            for (j, (x_j, _)) in points.iter().enumerate() {
+                if i != j {
+                    basis = &basis * &Self::new(vec![-*x_j, F::one()]);
+                    denominator *= *x_i - x_j;
+                }
+            }
+
+            let scale = *y_i * denominator.inverse().expect("interpolation points must be distinct");
+            result = &result + &basis.scalar_mul(scale);
+        }
+
+        result
+    }
+
+    // Long division, returns (quotient, remainder).
+    pub fn div_rem(&self, divisor: &Self) -> (Self, Self) {
+        assert!(!divisor.is_zero(), "division by the zero polynomial");
+
+        if self.coefficients.len() < divisor.coefficients.len() {
+            return (Self::zero(), self.clone());
+        }
+
+        let mut remainder = self.coefficients.clone();
+        let mut quotient = vec![F::zero(); self.coefficients.len() - divisor.coefficients.len() + 1];
+        let leading_inverse = divisor.coefficients.last().unwrap().inverse().unwrap();
+
+        for i in (0..quotient.len()).rev() {
+            let factor = remainder[i + divisor.degree()] * leading_inverse;
+            quotient[i] = factor;
+
+            for (j, coefficient) in divisor.coefficients.iter().enumerate() {
+                remainder[i + j] -= factor * coefficient;
+            }
+        }
+
+        (Self::new(quotient), Self::new(remainder))
+    }
+
+    // Division by X^n - 1, returns (quotient, remainder).
+    pub fn divide_by_vanishing(&self, n: usize) -> (Self, Self) {
+        if self.coefficients.len() <= n {
+            return (Self::zero(), self.clone());
+        }
+
+        // X^n = 1 modulo X^n - 1, so fold the coefficients from the top
+        let mut quotient = vec![F::zero(); self.coefficients.len() - n];
+        let mut remainder = self.coefficients.clone();
+
+        for i in (n..self.coefficients.len()).rev() {
+            let coefficient = remainder[i];
+            quotient[i - n] = coefficient;
+            remainder[i - n] += coefficient;
+            remainder[i] = F::zero();
+        }
+
+        (Self::new(quotient), Self::new(remainder))
+    }
+
+    fn trim(&mut self) {
+        while self.coefficients.last().is_some_and(|coefficient| coefficient.is_zero()) {
+            self.coefficients.pop();
+        }
+    }
 }
 
-impl UnivariateDense {
+impl<F: PrimeField> Add for &UnivariateDense<F> {
+    type Output = UnivariateDense<F>;
 
+    fn add(self, other: Self) -> UnivariateDense<F> {
+        let length = self.coefficients.len().max(other.coefficients.len());
+        let coefficients = (0..length)
+            .map(|i| {
+                self.coefficients.get(i).copied().unwrap_or(F::zero()) + other.coefficients.get(i).copied().unwrap_or(F::zero())
+            })
+            .collect();
 
-}
\ No newline at end of file
+        UnivariateDense::new(coefficients)
+    }
+}
+
+impl<F: PrimeField> Neg for &UnivariateDense<F> {
+    type Output = UnivariateDense<F>;
+
+    fn neg(self) -> UnivariateDense<F> {
+        UnivariateDense::new(self.coefficients.iter().map(|coefficient| -*coefficient).collect())
+    }
+}
+
+impl<F: PrimeField> Sub for &UnivariateDense<F> {
+    type Output = UnivariateDense<F>;
+
+    fn sub(self, other: Self) -> UnivariateDense<F> {
+        self + &(-other)
+    }
+}
+
+// schoolbook multiplication, use the NTT domain for large polynomials
+impl<F: PrimeField> Mul for &UnivariateDense<F> {
+    type Output = UnivariateDense<F>;
+
+    fn mul(self, other: Self) -> UnivariateDense<F> {
+        if self.is_zero() || other.is_zero() {
+            return UnivariateDense::zero();
+        }
+
+        let mut coefficients = vec![F::zero(); self.coefficients.len() + other.coefficients.len() - 1];
+        for (i, a) in self.coefficients.iter().enumerate() {
+            for (j, b) in other.coefficients.iter().enumerate() {
+                coefficients[i + j] += *a * b;
+            }
+        }
+
+        UnivariateDense::new(coefficients)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_bn254::Fr;
+
+    fn poly(coefficients: &[i64]) -> UnivariateDense<Fr> {
+        UnivariateDense::new(coefficients.iter().map(|c| Fr::from(*c)).collect())
+    }
+
+    #[test]
+    fn test_evaluate() {
+        // 1 + 2x + 3x^2
+        let p = poly(&[1, 2, 3]);
+        assert_eq!(p.evaluate(Fr::from(2)), Fr::from(17));
+        assert_eq!(p.degree(), 2);
+        assert_eq!(poly(&[4, 0, 0]).coefficients.len(), 1);
+    }
+
+    #[test]
+    fn test_arithmetic() {
+        let p = poly(&[1, 2, 3]);
+        let q = poly(&[4, 5]);
+
+        assert_eq!(&p + &q, poly(&[5, 7, 3]));
+        assert_eq!(&p - &p, UnivariateDense::zero());
+        assert_eq!(&p * &q, poly(&[4, 13, 22, 15]));
+    }
+
+    #[test]
+    fn test_interpolate() {
+        let points: Vec<(Fr, Fr)> = [(0, 1), (1, 6), (2, 17)].iter().map(|(x, y)| (Fr::from(*x), Fr::from(*y))).collect();
+
+        assert_eq!(UnivariateDense::interpolate(&points), poly(&[1, 2, 3]));
+    }
+
+    #[test]
+    fn test_div_rem() {
+        let p = poly(&[4, 13, 22, 15]);
+        let (quotient, remainder) = p.div_rem(&poly(&[4, 5]));
+        assert_eq!(quotient, poly(&[1, 2, 3]));
+        assert!(remainder.is_zero());
+
+        let (quotient, remainder) = (&p + &poly(&[1])).div_rem(&poly(&[1, 2, 3]));
+        assert_eq!(quotient, poly(&[4, 5]));
+        assert_eq!(remainder, poly(&[1]));
+    }
+
+    #[test]
+    fn test_divide_by_vanishing() {
+        // (x^2 - 1)(x + 3) + 2x + 1
+        let p = &(&poly(&[-1, 0, 1]) * &poly(&[3, 1])) + &poly(&[1, 2]);
+        let (quotient, remainder) = p.divide_by_vanishing(2);
+
+        assert_eq!(quotient, poly(&[3, 1]));
+        assert_eq!(remainder, poly(&[1, 2]));
+    }
+}